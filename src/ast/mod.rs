@@ -1,2 +1,3 @@
 pub mod nodes;
+pub mod printer;
 pub mod traits;
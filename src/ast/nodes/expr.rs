@@ -1,5 +1,8 @@
 use crate::{
-    ast::traits::{ExprVisitor, VisitExpr},
+    ast::{
+        nodes::{FunctionStmt, Pattern},
+        traits::{ExprVisitor, VisitExpr},
+    },
     literal::Literal,
     token::Token,
 };
@@ -12,8 +15,19 @@ pub enum Expr {
     Unary(Unary),
     Variable(Variable),
     Assign(Assign),
+    DestructureAssign(DestructureAssign),
     Logical(Logical),
     Call(Call),
+    List(List),
+    Index(Index),
+    IndexSet(IndexSet),
+    Map(Map),
+    Lambda(Lambda),
+    Get(Get),
+    Interpolation(Interpolation),
+    OptionalChain(OptionalChain),
+    Update(Update),
+    Conditional(Conditional),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -57,12 +71,97 @@ pub struct Assign {
     pub value: Box<Expr>,
 }
 
+// `[a, b] = [b, a]`, evaluating to the assigned value
+#[derive(Debug, PartialEq, Clone)]
+pub struct DestructureAssign {
+    pub pattern: Pattern,
+    pub value: Box<Expr>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Call {
     pub callee: Box<Expr>,
     // closing paren's token, for location reporting in error
     pub paren: Token,
     pub arguments: Vec<Expr>,
+    // `name: value` arguments, which follow the positional ones
+    pub named: Vec<(Token, Expr)>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct List {
+    // opening bracket's token, for location reporting in error
+    pub bracket: Token,
+    pub elements: Vec<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Map {
+    // opening brace's token, for location reporting in error
+    pub brace: Token,
+    pub entries: Vec<(Expr, Expr)>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Index {
+    pub object: Box<Expr>,
+    // closing bracket's token, for location reporting in error
+    pub bracket: Token,
+    pub index: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IndexSet {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+    pub value: Box<Expr>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lambda {
+    pub function: FunctionStmt,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Get {
+    pub object: Box<Expr>,
+    pub name: Token,
+    // `a?.b`, which is nil instead of an error when `a` is nil
+    pub optional: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Conditional {
+    pub condition: Box<Expr>,
+    pub question: Token,
+    pub then_branch: Box<Expr>,
+    pub else_branch: Box<Expr>,
+}
+
+// `"a ${b} c"`, whose parts are joined after stringifying those that aren't strings
+#[derive(Debug, PartialEq, Clone)]
+pub struct Interpolation {
+    pub parts: Vec<Expr>,
+}
+
+// A chain of calls, property accesses and indexing containing at least one
+// `?.`. When a `?.` finds nil, the rest of the chain is skipped and the whole
+// chain is nil, so `a?.b.c` doesn't fail on `.c` when `a` is nil.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OptionalChain {
+    pub expression: Box<Expr>,
+}
+
+// Compound assignment such as `x += 1`, and `++`/`--` which desugar to it.
+// `operator` is the binary operator applied, carrying the lexeme as written,
+// and a postfix update evaluates to the target's old value.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Update {
+    pub target: Box<Expr>,
+    pub operator: Token,
+    pub value: Box<Expr>,
+    pub postfix: bool,
 }
 
 // VisitExpr impl
@@ -119,3 +218,69 @@ impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Call {
         V::visit_call_expr(visitor, self)
     }
 }
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for List {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_list_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Index {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_index_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for IndexSet {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_index_set_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Map {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_map_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Lambda {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_lambda_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Get {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_get_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for OptionalChain {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_optional_chain_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Interpolation {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_interpolation_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Update {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_update_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for DestructureAssign {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_destructure_assign_expr(visitor, self)
+    }
+}
+
+impl<R, V: ExprVisitor<R>> VisitExpr<R, V> for Conditional {
+    fn accept(&self, visitor: &mut V) -> R {
+        V::visit_conditional_expr(visitor, self)
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     token::Token,
 };
 
-use super::Expr;
+use super::{Expr, Index};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Stmt {
@@ -11,11 +11,18 @@ pub enum Stmt {
     Expression(ExpressionStmt),
     Print(PrintStmt),
     Variable(VariableDeclarationStmt),
+    Destructure(DestructureStmt),
     If(IfStmt),
     While(WhileStmt),
     For(ForStmt),
+    ForIn(ForInStmt),
     Function(FunctionStmt),
     Return(ReturnStmt),
+    Break(BreakStmt),
+    Continue(ContinueStmt),
+    Throw(ThrowStmt),
+    Try(TryStmt),
+    Import(ImportStmt),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -37,6 +44,82 @@ pub struct PrintStmt {
 pub struct VariableDeclarationStmt {
     pub token: Token,
     pub initializer: Expr,
+    pub kind: DeclarationKind,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DeclarationKind {
+    Var,
+    // can't be redeclared in its scope or used before its declaration runs
+    Let,
+    // can't be assigned to after its declaration
+    Const,
+}
+
+impl DeclarationKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            DeclarationKind::Var => "var",
+            DeclarationKind::Let => "let",
+            DeclarationKind::Const => "const",
+        }
+    }
+}
+
+// `var [a, b] = pair;` or `var {x, y} = point;`
+#[derive(Debug, PartialEq, Clone)]
+pub struct DestructureStmt {
+    pub pattern: Pattern,
+    pub initializer: Expr,
+    pub kind: DeclarationKind,
+}
+
+// What a destructuring declaration or assignment binds: a name, or the
+// elements of a list or the entries of a map, matched against nested patterns.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Pattern {
+    Name(Token),
+    // `xs[0]`, only in assignments
+    Index(Index),
+    List {
+        bracket: Token,
+        elements: Vec<PatternElement>,
+    },
+    // each key is looked up as a string, `{x}` being short for `{x: x}`
+    Map {
+        brace: Token,
+        entries: Vec<(Token, PatternElement)>,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PatternElement {
+    pub target: Pattern,
+    // used when the value is missing or nil
+    pub default: Option<Expr>,
+}
+
+impl Pattern {
+    // the names bound by the pattern, in order
+    pub fn names(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Name(name) => vec![name],
+            _ => self
+                .elements()
+                .into_iter()
+                .flat_map(|element| element.target.names())
+                .collect(),
+        }
+    }
+
+    // the nested elements of a list or map pattern
+    pub fn elements(&self) -> Vec<&PatternElement> {
+        match self {
+            Pattern::Name(_) | Pattern::Index(_) => vec![],
+            Pattern::List { elements, .. } => elements.iter().collect(),
+            Pattern::Map { entries, .. } => entries.iter().map(|(_, element)| element).collect(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -60,19 +143,90 @@ pub struct ForStmt {
     pub body: Box<Stmt>,
 }
 
+// `for (var name in iterable) body`, binding `name` afresh for each item
+#[derive(Debug, PartialEq, Clone)]
+pub struct ForInStmt {
+    pub name: Token,
+    pub kind: DeclarationKind,
+    // the `in` keyword's token, for location reporting in error
+    pub keyword: Token,
+    pub iterable: Iterable,
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Iterable {
+    Value(Expr),
+    // `start..end`, or `start..=end` to include the end, counted without
+    // building a list
+    Range {
+        start: Expr,
+        operator: Token,
+        end: Expr,
+    },
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct FunctionStmt {
     pub name: Box<Token>,
-    pub params: Vec<Token>,
+    pub params: Vec<Param>,
+    // `...rest`, collecting surplus positional arguments into a list
+    pub rest: Option<Token>,
     pub body: Vec<Stmt>,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Param {
+    pub name: Token,
+    // evaluated at call time, in the callee's environment
+    pub default: Option<Expr>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct ReturnStmt {
     pub token: Token,
     pub value: Expr,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct BreakStmt {
+    pub token: Token,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContinueStmt {
+    pub token: Token,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ThrowStmt {
+    pub token: Token,
+    pub value: Expr,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CatchClause {
+    pub name: Token,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TryStmt {
+    pub body: Vec<Stmt>,
+    pub catch: Option<CatchClause>,
+    pub finally: Option<Vec<Stmt>>,
+}
+
+// `import "path" as alias;` binds the module itself, while
+// `from "path" import a, b;` binds the listed names from it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportStmt {
+    pub keyword: Token,
+    pub path: Token,
+    pub alias: Option<Token>,
+    pub names: Vec<Token>,
+}
+
 // VisitStmt impls
 
 impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for BlockStmt {
@@ -105,6 +259,12 @@ impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for VariableDeclarationStmt {
     }
 }
 
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for DestructureStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_destructure(self)
+    }
+}
+
 impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for IfStmt {
     fn accept(&self, visitor: &mut V) -> R {
         visitor.visit_if(self)
@@ -123,6 +283,12 @@ impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for ForStmt {
     }
 }
 
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for ForInStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_for_in(self)
+    }
+}
+
 impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for FunctionStmt {
     fn accept(&self, visitor: &mut V) -> R {
         visitor.visit_function(self)
@@ -134,3 +300,33 @@ impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for ReturnStmt {
         visitor.visit_return(self)
     }
 }
+
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for BreakStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_break(self)
+    }
+}
+
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for ContinueStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_continue(self)
+    }
+}
+
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for ThrowStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_throw(self)
+    }
+}
+
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for TryStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_try(self)
+    }
+}
+
+impl<R, V: StmtVisitor<R>> VisitStmt<R, V> for ImportStmt {
+    fn accept(&self, visitor: &mut V) -> R {
+        visitor.visit_import(self)
+    }
+}
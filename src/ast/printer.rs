@@ -0,0 +1,411 @@
+use super::{
+    nodes::*,
+    traits::{ExprVisitor, StmtVisitor, VisitExpr, VisitStmt},
+};
+use crate::literal::Literal;
+
+// Renders the tree as s-expressions, one top level statement per line.
+pub struct AstPrinter;
+
+impl AstPrinter {
+    pub fn print(&mut self, statements: &[Stmt]) -> String {
+        statements
+            .iter()
+            .map(|statement| statement.accept(self))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn parenthesize(&mut self, name: &str, exprs: &[&Expr]) -> String {
+        let mut output = format!("({}", name);
+        for expr in exprs {
+            output.push(' ');
+            output.push_str(&expr.accept(self));
+        }
+        output.push(')');
+        output
+    }
+
+    fn params(&mut self, function: &FunctionStmt) -> String {
+        let mut params: Vec<String> = vec![];
+        for param in &function.params {
+            match &param.default {
+                Some(default) => {
+                    params.push(format!("{}={}", param.name.lexeme, default.accept(self)))
+                }
+                None => params.push(param.name.lexeme.to_string()),
+            }
+        }
+        if let Some(rest) = &function.rest {
+            params.push(format!("...{}", rest.lexeme));
+        }
+        params.join(" ")
+    }
+
+    // `[a b=1 {x: x y: [p q]}]`
+    fn pattern(&mut self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Name(name) => name.lexeme.to_string(),
+            Pattern::Index(index) => self.visit_index_expr(index),
+            Pattern::List { elements, .. } => {
+                let elements: Vec<String> = elements
+                    .iter()
+                    .map(|element| self.pattern_element(element))
+                    .collect();
+                format!("[{}]", elements.join(" "))
+            }
+            Pattern::Map { entries, .. } => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, element)| {
+                        format!("{}: {}", key.lexeme, self.pattern_element(element))
+                    })
+                    .collect();
+                format!("{{{}}}", entries.join(" "))
+            }
+        }
+    }
+
+    fn pattern_element(&mut self, element: &PatternElement) -> String {
+        let target = self.pattern(&element.target);
+        match &element.default {
+            Some(default) => format!("{}={}", target, default.accept(self)),
+            None => target,
+        }
+    }
+
+    fn statements(&mut self, name: &str, statements: &[Stmt]) -> String {
+        let mut output = format!("({}", name);
+        for statement in statements {
+            output.push(' ');
+            output.push_str(&statement.accept(self));
+        }
+        output.push(')');
+        output
+    }
+}
+
+impl StmtVisitor<String> for AstPrinter {
+    fn visit_statement(&mut self, stmt: &Stmt) -> String {
+        match stmt {
+            Stmt::Block(block_stmt) => self.visit_block(block_stmt),
+            Stmt::Expression(expr_stmt) => self.visit_expression(expr_stmt),
+            Stmt::Print(print_stmt) => self.visit_print(print_stmt),
+            Stmt::Variable(variable_stmt) => self.visit_variable_declaration(variable_stmt),
+            Stmt::Destructure(destructure_stmt) => self.visit_destructure(destructure_stmt),
+            Stmt::If(if_stmt) => self.visit_if(if_stmt),
+            Stmt::While(while_stmt) => self.visit_while(while_stmt),
+            Stmt::For(for_stmt) => self.visit_for(for_stmt),
+            Stmt::ForIn(for_in_stmt) => self.visit_for_in(for_in_stmt),
+            Stmt::Function(function_stmt) => self.visit_function(function_stmt),
+            Stmt::Return(return_stmt) => self.visit_return(return_stmt),
+            Stmt::Break(break_stmt) => self.visit_break(break_stmt),
+            Stmt::Continue(continue_stmt) => self.visit_continue(continue_stmt),
+            Stmt::Throw(throw_stmt) => self.visit_throw(throw_stmt),
+            Stmt::Try(try_stmt) => self.visit_try(try_stmt),
+            Stmt::Import(import_stmt) => self.visit_import(import_stmt),
+        }
+    }
+
+    fn visit_expression(&mut self, expr_stmt: &ExpressionStmt) -> String {
+        self.parenthesize(";", &[&expr_stmt.expression])
+    }
+
+    fn visit_print(&mut self, print_stmt: &PrintStmt) -> String {
+        self.parenthesize("print", &[&print_stmt.expression])
+    }
+
+    fn visit_variable_declaration(&mut self, variable_stmt: &VariableDeclarationStmt) -> String {
+        let name = format!(
+            "{} {}",
+            variable_stmt.kind.keyword(),
+            variable_stmt.token.lexeme
+        );
+        self.parenthesize(&name, &[&variable_stmt.initializer])
+    }
+
+    fn visit_destructure(&mut self, destructure_stmt: &DestructureStmt) -> String {
+        let name = format!(
+            "{} {}",
+            destructure_stmt.kind.keyword(),
+            self.pattern(&destructure_stmt.pattern)
+        );
+        self.parenthesize(&name, &[&destructure_stmt.initializer])
+    }
+
+    fn visit_block(&mut self, block_stmt: &BlockStmt) -> String {
+        self.statements("block", &block_stmt.statements)
+    }
+
+    fn visit_if(&mut self, if_stmt: &IfStmt) -> String {
+        let mut output = format!(
+            "(if {} {}",
+            if_stmt.condition.accept(self),
+            if_stmt.then_branch.accept(self)
+        );
+        if let Some(else_branch) = &if_stmt.else_branch {
+            output.push(' ');
+            output.push_str(&else_branch.accept(self));
+        }
+        output.push(')');
+        output
+    }
+
+    fn visit_while(&mut self, while_stmt: &WhileStmt) -> String {
+        format!(
+            "(while {} {})",
+            while_stmt.condition.accept(self),
+            while_stmt.body.accept(self)
+        )
+    }
+
+    fn visit_for(&mut self, for_stmt: &ForStmt) -> String {
+        let initializer = match &for_stmt.initializer {
+            Some(initializer) => initializer.accept(self),
+            None => String::from("_"),
+        };
+        let condition = match &for_stmt.condition {
+            Some(condition) => condition.accept(self),
+            None => String::from("_"),
+        };
+        let increment = match &for_stmt.increment {
+            Some(increment) => increment.accept(self),
+            None => String::from("_"),
+        };
+
+        format!(
+            "(for {} {} {} {})",
+            initializer,
+            condition,
+            increment,
+            for_stmt.body.accept(self)
+        )
+    }
+
+    fn visit_for_in(&mut self, for_in_stmt: &ForInStmt) -> String {
+        let iterable = match &for_in_stmt.iterable {
+            Iterable::Value(value) => value.accept(self),
+            Iterable::Range {
+                start,
+                operator,
+                end,
+            } => self.parenthesize(&operator.lexeme, &[start, end]),
+        };
+
+        format!(
+            "(for-in {} {} {} {})",
+            for_in_stmt.kind.keyword(),
+            for_in_stmt.name.lexeme,
+            iterable,
+            for_in_stmt.body.accept(self)
+        )
+    }
+
+    fn visit_function(&mut self, function_stmt: &FunctionStmt) -> String {
+        let params = self.params(function_stmt);
+        let name = format!("fun {} ({})", function_stmt.name.lexeme, params);
+        self.statements(&name, &function_stmt.body)
+    }
+
+    fn visit_return(&mut self, return_stmt: &ReturnStmt) -> String {
+        self.parenthesize("return", &[&return_stmt.value])
+    }
+
+    fn visit_break(&mut self, _break_stmt: &BreakStmt) -> String {
+        String::from("(break)")
+    }
+
+    fn visit_continue(&mut self, _continue_stmt: &ContinueStmt) -> String {
+        String::from("(continue)")
+    }
+
+    fn visit_throw(&mut self, throw_stmt: &ThrowStmt) -> String {
+        self.parenthesize("throw", &[&throw_stmt.value])
+    }
+
+    fn visit_try(&mut self, try_stmt: &TryStmt) -> String {
+        let mut output = self.statements("try", &try_stmt.body);
+        output.pop();
+        if let Some(catch) = &try_stmt.catch {
+            let name = format!("catch {}", catch.name.lexeme);
+            output.push(' ');
+            output.push_str(&self.statements(&name, &catch.body));
+        }
+        if let Some(finally) = &try_stmt.finally {
+            output.push(' ');
+            output.push_str(&self.statements("finally", finally));
+        }
+        output.push(')');
+        output
+    }
+
+    fn visit_import(&mut self, import_stmt: &ImportStmt) -> String {
+        let path = format!("{:?}", import_stmt.path.literal.to_string());
+        match &import_stmt.alias {
+            Some(alias) => format!("(import {} as {})", path, alias.lexeme),
+            None => {
+                let names = import_stmt
+                    .names
+                    .iter()
+                    .map(|name| name.lexeme.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                format!("(from {} import {})", path, names)
+            }
+        }
+    }
+}
+
+impl ExprVisitor<String> for AstPrinter {
+    fn visit_expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Binary(binary) => self.visit_binary_expr(binary),
+            Expr::Grouping(grouping) => self.visit_grouping_expr(grouping),
+            Expr::Literal(lit) => self.visit_literal_expr(lit),
+            Expr::Unary(unary) => self.visit_unary_expr(unary),
+            Expr::Variable(variable) => self.visit_variable_expr(variable),
+            Expr::Assign(assign) => self.visit_assign_expr(assign),
+            Expr::DestructureAssign(destructure) => self.visit_destructure_assign_expr(destructure),
+            Expr::Logical(logical) => self.visit_logical_expr(logical),
+            Expr::Call(call) => self.visit_call_expr(call),
+            Expr::List(list) => self.visit_list_expr(list),
+            Expr::Index(index) => self.visit_index_expr(index),
+            Expr::IndexSet(index_set) => self.visit_index_set_expr(index_set),
+            Expr::Map(map) => self.visit_map_expr(map),
+            Expr::Lambda(lambda) => self.visit_lambda_expr(lambda),
+            Expr::Get(get) => self.visit_get_expr(get),
+            Expr::Interpolation(interpolation) => self.visit_interpolation_expr(interpolation),
+            Expr::OptionalChain(chain) => self.visit_optional_chain_expr(chain),
+            Expr::Update(update) => self.visit_update_expr(update),
+            Expr::Conditional(conditional) => self.visit_conditional_expr(conditional),
+        }
+    }
+
+    fn visit_binary_expr(&mut self, binary_expr: &Binary) -> String {
+        self.parenthesize(
+            &binary_expr.operator.lexeme,
+            &[&binary_expr.left, &binary_expr.right],
+        )
+    }
+
+    fn visit_grouping_expr(&mut self, grouping_expr: &Grouping) -> String {
+        self.parenthesize("group", &[&grouping_expr.expression])
+    }
+
+    fn visit_literal_expr(&mut self, literal_expr: &Lit) -> String {
+        match &literal_expr.literal {
+            Literal::String(string) => format!("{:?}", string),
+            literal => literal.to_string(),
+        }
+    }
+
+    fn visit_unary_expr(&mut self, unary_expr: &Unary) -> String {
+        self.parenthesize(&unary_expr.operator.lexeme, &[&unary_expr.right])
+    }
+
+    fn visit_variable_expr(&mut self, variable_expr: &Variable) -> String {
+        variable_expr.token.lexeme.to_string()
+    }
+
+    fn visit_assign_expr(&mut self, assign_expr: &Assign) -> String {
+        let name = format!("= {}", assign_expr.token.lexeme);
+        self.parenthesize(&name, &[&assign_expr.value])
+    }
+
+    fn visit_destructure_assign_expr(&mut self, destructure_expr: &DestructureAssign) -> String {
+        let name = format!("= {}", self.pattern(&destructure_expr.pattern));
+        self.parenthesize(&name, &[&destructure_expr.value])
+    }
+
+    fn visit_logical_expr(&mut self, logical_expr: &Logical) -> String {
+        self.parenthesize(
+            &logical_expr.operator.lexeme,
+            &[&logical_expr.left, &logical_expr.right],
+        )
+    }
+
+    fn visit_call_expr(&mut self, call_expr: &Call) -> String {
+        let mut exprs: Vec<&Expr> = vec![&call_expr.callee];
+        exprs.extend(call_expr.arguments.iter());
+        let mut output = self.parenthesize("call", &exprs);
+        output.pop();
+        for (name, argument) in &call_expr.named {
+            output.push_str(&format!(" {}: {}", name.lexeme, argument.accept(self)));
+        }
+        output.push(')');
+        output
+    }
+
+    fn visit_list_expr(&mut self, list_expr: &List) -> String {
+        let elements: Vec<&Expr> = list_expr.elements.iter().collect();
+        self.parenthesize("list", &elements)
+    }
+
+    fn visit_map_expr(&mut self, map_expr: &Map) -> String {
+        let entries: Vec<&Expr> = map_expr
+            .entries
+            .iter()
+            .flat_map(|(key, value)| [key, value])
+            .collect();
+        self.parenthesize("map", &entries)
+    }
+
+    fn visit_index_expr(&mut self, index_expr: &Index) -> String {
+        self.parenthesize("[]", &[&index_expr.object, &index_expr.index])
+    }
+
+    fn visit_index_set_expr(&mut self, index_set_expr: &IndexSet) -> String {
+        self.parenthesize(
+            "[]=",
+            &[
+                &index_set_expr.object,
+                &index_set_expr.index,
+                &index_set_expr.value,
+            ],
+        )
+    }
+
+    fn visit_lambda_expr(&mut self, lambda_expr: &Lambda) -> String {
+        let params = self.params(&lambda_expr.function);
+        let name = format!("fun ({})", params);
+        self.statements(&name, &lambda_expr.function.body)
+    }
+
+    fn visit_get_expr(&mut self, get_expr: &Get) -> String {
+        let operator = if get_expr.optional { "?." } else { "." };
+        let name = format!("{} {}", operator, get_expr.name.lexeme);
+        self.parenthesize(&name, &[&get_expr.object])
+    }
+
+    fn visit_interpolation_expr(&mut self, interpolation_expr: &Interpolation) -> String {
+        let parts: Vec<&Expr> = interpolation_expr.parts.iter().collect();
+        self.parenthesize("interpolate", &parts)
+    }
+
+    fn visit_optional_chain_expr(&mut self, chain_expr: &OptionalChain) -> String {
+        self.parenthesize("chain", &[&chain_expr.expression])
+    }
+
+    fn visit_update_expr(&mut self, update_expr: &Update) -> String {
+        let operator = &update_expr.operator.lexeme;
+        match (operator.as_ref(), update_expr.postfix) {
+            ("++" | "--", false) => self.parenthesize(operator, &[&update_expr.target]),
+            ("++" | "--", true) => {
+                let name = format!("post{}", operator);
+                self.parenthesize(&name, &[&update_expr.target])
+            }
+            _ => self.parenthesize(operator, &[&update_expr.target, &update_expr.value]),
+        }
+    }
+
+    fn visit_conditional_expr(&mut self, conditional_expr: &Conditional) -> String {
+        self.parenthesize(
+            "?:",
+            &[
+                &conditional_expr.condition,
+                &conditional_expr.then_branch,
+                &conditional_expr.else_branch,
+            ],
+        )
+    }
+}
@@ -12,8 +12,19 @@ pub trait ExprVisitor<R> {
     fn visit_unary_expr(&mut self, unary_expr: &Unary) -> R;
     fn visit_variable_expr(&mut self, variable_expr: &Variable) -> R;
     fn visit_assign_expr(&mut self, assign_expr: &Assign) -> R;
+    fn visit_destructure_assign_expr(&mut self, destructure_expr: &DestructureAssign) -> R;
     fn visit_logical_expr(&mut self, logical_expr: &Logical) -> R;
     fn visit_call_expr(&mut self, call_expr: &Call) -> R;
+    fn visit_list_expr(&mut self, list_expr: &List) -> R;
+    fn visit_map_expr(&mut self, map_expr: &Map) -> R;
+    fn visit_index_expr(&mut self, index_expr: &Index) -> R;
+    fn visit_index_set_expr(&mut self, index_set_expr: &IndexSet) -> R;
+    fn visit_lambda_expr(&mut self, lambda_expr: &Lambda) -> R;
+    fn visit_get_expr(&mut self, get_expr: &Get) -> R;
+    fn visit_interpolation_expr(&mut self, interpolation_expr: &Interpolation) -> R;
+    fn visit_optional_chain_expr(&mut self, chain_expr: &OptionalChain) -> R;
+    fn visit_update_expr(&mut self, update_expr: &Update) -> R;
+    fn visit_conditional_expr(&mut self, conditional_expr: &Conditional) -> R;
 }
 
 pub trait VisitStmt<R, V: StmtVisitor<R>> {
@@ -25,10 +36,17 @@ pub trait StmtVisitor<R> {
     fn visit_expression(&mut self, expr_stmt: &ExpressionStmt) -> R;
     fn visit_print(&mut self, print_stmt: &PrintStmt) -> R;
     fn visit_variable_declaration(&mut self, variable_stmt: &VariableDeclarationStmt) -> R;
+    fn visit_destructure(&mut self, destructure_stmt: &DestructureStmt) -> R;
     fn visit_block(&mut self, block_stmt: &BlockStmt) -> R;
     fn visit_if(&mut self, if_stmt: &IfStmt) -> R;
     fn visit_while(&mut self, while_stmt: &WhileStmt) -> R;
     fn visit_for(&mut self, for_stmt: &ForStmt) -> R;
+    fn visit_for_in(&mut self, for_in_stmt: &ForInStmt) -> R;
     fn visit_function(&mut self, function_stmt: &FunctionStmt) -> R;
     fn visit_return(&mut self, return_stmt: &ReturnStmt) -> R;
+    fn visit_break(&mut self, break_stmt: &BreakStmt) -> R;
+    fn visit_continue(&mut self, continue_stmt: &ContinueStmt) -> R;
+    fn visit_throw(&mut self, throw_stmt: &ThrowStmt) -> R;
+    fn visit_try(&mut self, try_stmt: &TryStmt) -> R;
+    fn visit_import(&mut self, import_stmt: &ImportStmt) -> R;
 }
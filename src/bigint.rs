@@ -0,0 +1,475 @@
+use std::{
+    cmp::Ordering,
+    convert::Infallible,
+    fmt::{self, Display, Formatter},
+    mem::size_of,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+// An arbitrary-precision integer: a sign and a magnitude in base 2^32, least
+// significant limb first. The magnitude never has leading zero limbs and zero
+// is never negative, so equal values are structurally equal.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+const BASE: f64 = 4294967296.0;
+
+impl BigInt {
+    fn new(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    // Parses digits in `radix` with an optional leading sign.
+    pub fn parse(string: &str, radix: u32) -> Option<Self> {
+        let (negative, digits) = match string.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, string.strip_prefix('+').unwrap_or(string)),
+        };
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut magnitude = vec![];
+        for char in digits.chars() {
+            let digit = char.to_digit(radix)?;
+            mul_add_small(&mut magnitude, radix, digit);
+        }
+        Some(Self::new(negative, magnitude))
+    }
+
+    // The integer a float holds, if it is finite and has no fractional part.
+    pub fn from_f64(number: f64) -> Option<Self> {
+        if !number.is_finite() || number.fract() != 0.0 {
+            return None;
+        }
+        // dividing an integral float by a power of two is exact
+        let mut rest = number.abs();
+        let mut magnitude = vec![];
+        while rest >= 1.0 {
+            let limb = rest % BASE;
+            magnitude.push(limb as u32);
+            rest = (rest - limb) / BASE;
+        }
+        Some(Self::new(number < 0.0, magnitude))
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0i128, |value, limb| value << 32 | *limb as i128);
+        let value = if self.negative { -magnitude } else { magnitude };
+        i64::try_from(value).ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0.0, |value, limb| value * BASE + *limb as f64);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    // The number of bits in the magnitude, 0 for zero.
+    pub fn bits(&self) -> u64 {
+        match self.magnitude.last() {
+            Some(last) => self.magnitude.len() as u64 * 32 - last.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn heap_size(&self) -> usize {
+        size_of::<Self>() + self.magnitude.len() * size_of::<u32>()
+    }
+
+    // Floored division, so the remainder takes the sign of the divisor.
+    // `None` when dividing by zero.
+    pub fn div_mod_floor(&self, divisor: &BigInt) -> Option<(BigInt, BigInt)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem_magnitude(&self.magnitude, &divisor.magnitude);
+        let quotient = BigInt::new(self.negative != divisor.negative, quotient);
+        let remainder = BigInt::new(self.negative, remainder);
+        if !remainder.is_zero() && remainder.negative != divisor.negative {
+            Some((&quotient - &BigInt::from(1), &remainder + divisor))
+        } else {
+            Some((quotient, remainder))
+        }
+    }
+
+    // `self * other`, calling `tick` before each row of the long
+    // multiplication so that a caller can stop one that runs too long.
+    pub fn mul_with<E>(
+        &self,
+        other: &BigInt,
+        tick: &mut impl FnMut() -> Result<(), E>,
+    ) -> Result<BigInt, E> {
+        let mut product = vec![0u32; self.magnitude.len() + other.magnitude.len()];
+        for (i, left) in self.magnitude.iter().enumerate() {
+            tick()?;
+            let mut carry = 0u64;
+            for (j, right) in other.magnitude.iter().enumerate() {
+                let sum = product[i + j] as u64 + *left as u64 * *right as u64 + carry;
+                product[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            product[i + other.magnitude.len()] = carry as u32;
+        }
+        Ok(BigInt::new(self.negative != other.negative, product))
+    }
+
+    // `self ** exponent` by repeated squaring, calling `tick` as `mul_with` does.
+    pub fn pow_with<E>(
+        &self,
+        mut exponent: u32,
+        tick: &mut impl FnMut() -> Result<(), E>,
+    ) -> Result<BigInt, E> {
+        let mut result = BigInt::from(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            tick()?;
+            if exponent & 1 == 1 {
+                result = result.mul_with(&base, tick)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul_with(&base, tick)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+        Self::new(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(
+                self.negative,
+                add_magnitude(&self.magnitude, &other.magnitude),
+            );
+        }
+        // opposite signs, so the larger magnitude decides the sign
+        match compare_magnitude(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::new(
+                other.negative,
+                sub_magnitude(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::new(
+                self.negative,
+                sub_magnitude(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        match self.mul_with(other, &mut || Ok::<_, Infallible>(())) {
+            Ok(product) => product,
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // peel off nine decimal digits at a time, least significant first
+        let mut chunks = vec![];
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            chunks.push(div_rem_small(&mut rest, 1_000_000_000));
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn compare_magnitude(left: &[u32], right: &[u32]) -> Ordering {
+    left.len()
+        .cmp(&right.len())
+        .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+fn add_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(left.len().max(right.len()) + 1);
+    let mut carry = 0u64;
+    for index in 0..left.len().max(right.len()) {
+        let total =
+            *left.get(index).unwrap_or(&0) as u64 + *right.get(index).unwrap_or(&0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    sum.push(carry as u32);
+    sum
+}
+
+// `left - right` where `left` is at least `right`.
+fn sub_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(left.len());
+    let mut borrow = 0i64;
+    for (index, limb) in left.iter().enumerate() {
+        let mut total = *limb as i64 - *right.get(index).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if total < 0 {
+            total += 1 << 32;
+            borrow = 1;
+        }
+        difference.push(total as u32);
+    }
+    difference
+}
+
+// magnitude = magnitude * factor + addend
+fn mul_add_small(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in magnitude.iter_mut() {
+        let total = *limb as u64 * factor as u64 + carry;
+        *limb = total as u32;
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+// Divides the magnitude in place, returning the remainder.
+fn div_rem_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for limb in magnitude.iter_mut().rev() {
+        let current = remainder << 32 | *limb as u64;
+        *limb = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+// Truncated division of magnitudes. Single-limb divisors take the fast path,
+// anything wider falls back to binary long division.
+fn div_rem_magnitude(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = divisor {
+        let mut quotient = dividend.to_vec();
+        let remainder = div_rem_small(&mut quotient, *divisor);
+        return (quotient, vec![remainder]);
+    }
+
+    let mut quotient = vec![0u32; dividend.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for bit in (0..dividend.len() * 32).rev() {
+        // remainder = remainder * 2 + next bit of the dividend
+        mul_add_small(&mut remainder, 2, dividend[bit / 32] >> (bit % 32) & 1);
+        if compare_magnitude(&remainder, divisor) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, divisor);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(string: &str) -> BigInt {
+        BigInt::parse(string, 10).unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints_signs() {
+        assert_eq!(big("-0"), big("0"));
+        assert_eq!(big("+42").to_string(), "42");
+        assert_eq!(big("-42").to_string(), "-42");
+        assert_eq!(
+            big("-1000000000000000000000").to_string(),
+            "-1000000000000000000000"
+        );
+        assert_eq!(BigInt::parse("-ff", 16), Some(big("-255")));
+        assert_eq!(BigInt::parse("", 10), None);
+        assert_eq!(BigInt::parse("-", 10), None);
+        assert_eq!(BigInt::parse("12a", 10), None);
+    }
+
+    #[test]
+    fn adds_and_subtracts_across_signs() {
+        assert_eq!(&big("5") + &big("-7"), big("-2"));
+        assert_eq!(&big("-5") + &big("7"), big("2"));
+        assert_eq!(&big("-5") + &big("-7"), big("-12"));
+        assert_eq!(&big("5") - &big("5"), big("0"));
+        assert!(!(&big("-5") + &big("5")).negative);
+        assert_eq!(-&big("0"), big("0"));
+    }
+
+    #[test]
+    fn carries_and_borrows_between_limbs() {
+        // 2^32 - 1 + 1 carries into a new limb, and back again
+        let limb_max = big("4294967295");
+        let limb_base = &limb_max + &BigInt::from(1);
+        assert_eq!(limb_base, big("4294967296"));
+        assert_eq!(&limb_base - &BigInt::from(1), limb_max);
+        assert_eq!(
+            &big("18446744073709551616") - &BigInt::from(1),
+            big("18446744073709551615")
+        );
+        assert_eq!(
+            &big("340282366920938463463374607431768211456") - &big("1"),
+            big("340282366920938463463374607431768211455")
+        );
+        assert_eq!(&limb_max * &limb_max, big("18446744065119617025"));
+        assert_eq!(&big("-3") * &big("4"), big("-12"));
+        assert_eq!(&big("-3") * &big("0"), big("0"));
+    }
+
+    #[test]
+    fn divides_with_floored_remainders() {
+        let div_mod = |left: &str, right: &str| {
+            let (quotient, remainder) = big(left).div_mod_floor(&big(right)).unwrap();
+            (quotient.to_string(), remainder.to_string())
+        };
+        assert_eq!(div_mod("7", "3"), ("2".into(), "1".into()));
+        assert_eq!(div_mod("-7", "3"), ("-3".into(), "2".into()));
+        assert_eq!(div_mod("7", "-3"), ("-3".into(), "-2".into()));
+        assert_eq!(div_mod("-7", "-3"), ("2".into(), "-1".into()));
+        assert_eq!(div_mod("-6", "3"), ("-2".into(), "0".into()));
+        // multi-limb divisors take the long division path
+        assert_eq!(
+            div_mod("-100000000000000000000000", "30000000000000000000"),
+            ("-3334".into(), "20000000000000000000".into())
+        );
+        assert_eq!(big("1").div_mod_floor(&big("0")), None);
+    }
+
+    #[test]
+    fn round_trips_at_the_i64_boundaries() {
+        for value in [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX] {
+            assert_eq!(BigInt::from(value).to_i64(), Some(value));
+            assert_eq!(BigInt::from(value).to_string(), value.to_string());
+        }
+        assert_eq!(big("9223372036854775808").to_i64(), None);
+        assert_eq!(big("-9223372036854775809").to_i64(), None);
+        assert_eq!(
+            (&BigInt::from(i64::MAX) + &BigInt::from(1)).to_string(),
+            "9223372036854775808"
+        );
+        assert_eq!(-&BigInt::from(i64::MIN), big("9223372036854775808"));
+    }
+
+    #[test]
+    fn converts_floats() {
+        assert_eq!(BigInt::from_f64(-1e20), Some(big("-100000000000000000000")));
+        assert_eq!(BigInt::from_f64(0.5), None);
+        assert_eq!(BigInt::from_f64(f64::INFINITY), None);
+        assert_eq!(big("-100000000000000000000").to_f64(), -1e20);
+    }
+
+    #[test]
+    fn raises_to_powers() {
+        let pow = |base: i64, exponent| {
+            BigInt::from(base)
+                .pow_with(exponent, &mut || Ok::<_, Infallible>(()))
+                .unwrap()
+        };
+        assert_eq!(pow(2, 64), big("18446744073709551616"));
+        assert_eq!(pow(-3, 3), big("-27"));
+        assert_eq!(pow(-3, 4), big("81"));
+        assert_eq!(pow(7, 0), big("1"));
+        assert_eq!(pow(2, 64).bits(), 65);
+        assert_eq!(BigInt::from(0).bits(), 0);
+
+        let mut ticks = 0;
+        let stopped = BigInt::from(2).pow_with(1 << 20, &mut || {
+            ticks += 1;
+            if ticks > 10 {
+                Err("stopped")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(stopped, Err("stopped"));
+    }
+}
@@ -0,0 +1,101 @@
+use std::{env, path::PathBuf, time::Duration};
+
+use crate::interpreter::DEFAULT_MAX_CALL_DEPTH;
+
+// Host stack reserved per Lox call, so the interpreter reaches its own call
+// depth limit well before the native stack runs out.
+pub const STACK_BYTES_PER_CALL: usize = 64 * 1024;
+pub const MAX_STACK_BYTES: usize = 1024 * 1024 * 1024;
+// the deepest call stack the largest interpreter thread has room for
+const MAX_CALL_DEPTH: usize = MAX_STACK_BYTES / STACK_BYTES_PER_CALL;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Emit {
+    Run,
+    OptimizedAst,
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub filename: String,
+    pub opt_level: u8,
+    pub emit: Emit,
+    pub max_call_depth: usize,
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub max_memory: Option<usize>,
+    pub memory_stats: bool,
+    // directories searched for imports not found next to the importing file
+    pub module_path: Vec<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            filename: String::from("test.lox"),
+            opt_level: 1,
+            emit: Emit::Run,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            max_steps: None,
+            timeout: None,
+            max_memory: None,
+            memory_stats: false,
+            module_path: vec![],
+        }
+    }
+}
+
+impl Options {
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+
+        for arg in args {
+            if !arg.starts_with("--") {
+                options.filename = arg;
+                continue;
+            }
+
+            let (name, value) = arg.split_once('=').unwrap_or((arg.as_str(), ""));
+            match (name, value) {
+                ("--opt-level", "0") => options.opt_level = 0,
+                ("--opt-level", "1") => options.opt_level = 1,
+                ("--opt-level", _) => {
+                    return Err(format!("Invalid value '{}' for --opt-level.", value))
+                }
+                ("--emit", "optimized-ast") => options.emit = Emit::OptimizedAst,
+                ("--emit", _) => return Err(format!("Invalid value '{}' for --emit.", value)),
+                ("--max-call-depth", _) => match value.parse() {
+                    Ok(depth @ 1..=MAX_CALL_DEPTH) => options.max_call_depth = depth,
+                    Ok(depth) if depth > MAX_CALL_DEPTH => {
+                        return Err(format!(
+                            "Invalid value '{}' for --max-call-depth, the most supported is {}.",
+                            value, MAX_CALL_DEPTH
+                        ))
+                    }
+                    _ => return Err(format!("Invalid value '{}' for --max-call-depth.", value)),
+                },
+                ("--max-steps", _) => match value.parse() {
+                    Ok(steps) => options.max_steps = Some(steps),
+                    _ => return Err(format!("Invalid value '{}' for --max-steps.", value)),
+                },
+                ("--timeout-ms", _) => match value.parse() {
+                    Ok(millis) => options.timeout = Some(Duration::from_millis(millis)),
+                    _ => return Err(format!("Invalid value '{}' for --timeout-ms.", value)),
+                },
+                ("--max-memory", _) => match value.parse() {
+                    Ok(bytes) => options.max_memory = Some(bytes),
+                    _ => return Err(format!("Invalid value '{}' for --max-memory.", value)),
+                },
+                ("--memory-stats", "") => options.memory_stats = true,
+                ("--module-path", _) => options.module_path.extend(env::split_paths(value)),
+                _ => return Err(format!("Unknown option '{}'.", name)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub const USAGE: &str = "Usage: lox-interpreter [--opt-level=0|1] [--emit=optimized-ast] \
+[--max-call-depth=N] [--max-steps=N] [--timeout-ms=N] [--max-memory=BYTES] [--memory-stats] \
+[--module-path=DIR[:DIR...]] [script]";
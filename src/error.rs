@@ -1,14 +1,28 @@
-use std::fmt::Display;
+use std::{fmt::Display, path::Path};
 
-use crate::token::{Token, TokenType};
+use crate::{
+    literal::Literal,
+    token::{Token, TokenType},
+};
 
 #[derive(Clone, Debug)]
 pub enum LoxError {
     Scanner { line: usize, message: String },
     Parser { token: Token, message: String },
     Runtime { token: Token, message: String },
+    StackOverflow { token: Token, trace: Vec<String> },
+    LimitExceeded { message: String },
+    // a value raised by `throw`, unwinding to the nearest enclosing catch
+    Thrown { token: Token, value: Box<Literal> },
+    // errors from a file other than the script being run, tagged with its path
+    Module { path: String, errors: Vec<LoxError> },
+    // a `?.` that found nil, unwinding to the end of its optional chain
+    ShortCircuit,
 }
 
+// frames shown at each end of a stack trace before the middle is elided
+const TRACE_EDGE: usize = 10;
+
 impl Display for LoxError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -17,6 +31,30 @@ impl Display for LoxError {
             }
             LoxError::Parser { token, message } => report(f, token, message),
             LoxError::Runtime { token, message } => report(f, token, message),
+            LoxError::LimitExceeded { message } => write!(f, "Error: {}", message),
+            LoxError::ShortCircuit => write!(f, "Error: '?.' outside an optional chain."),
+            LoxError::Thrown { token, value } => report(f, token, &value.to_string()),
+            LoxError::Module { path, errors } => {
+                for (index, error) in errors.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}: {}", path, error)?;
+                }
+                Ok(())
+            }
+            LoxError::StackOverflow { token, trace } => {
+                report(f, token, &String::from("Stack overflow."))?;
+                for (index, frame) in trace.iter().enumerate() {
+                    if index == TRACE_EDGE && trace.len() > 2 * TRACE_EDGE {
+                        write!(f, "\n...{} more frames...", trace.len() - 2 * TRACE_EDGE)?;
+                    }
+                    if index < TRACE_EDGE || index >= trace.len() - TRACE_EDGE {
+                        write!(f, "\n{}", frame)?;
+                    }
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -37,4 +75,16 @@ impl LoxError {
     pub fn log(&self) {
         eprintln!("{}", self)
     }
+
+    // Tags an error escaping the code of another file with that file's path.
+    // Errors already tagged keep the innermost file they came from.
+    pub fn in_module(self, path: &Path) -> LoxError {
+        match self {
+            LoxError::Runtime { .. } | LoxError::Thrown { .. } => LoxError::Module {
+                path: path.display().to_string(),
+                errors: vec![self],
+            },
+            error => error,
+        }
+    }
 }
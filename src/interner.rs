@@ -0,0 +1,25 @@
+use std::{collections::HashSet, rc::Rc};
+
+// Keeps a single shared copy of every identifier and string literal seen by
+// the scanner, so tokens and runtime values holding the same text point at
+// the same allocation.
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: HashSet<Rc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, text: &str) -> Rc<str> {
+        if let Some(interned) = self.strings.get(text) {
+            return Rc::clone(interned);
+        }
+
+        let interned: Rc<str> = Rc::from(text);
+        self.strings.insert(Rc::clone(&interned));
+        interned
+    }
+}
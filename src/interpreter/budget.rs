@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use crate::error::LoxError;
+
+// steps between two reads of the clock while a deadline is set
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+// Bounds how much work a single `Interpreter::interpret` run may do. Every
+// evaluated statement, expression and loop iteration counts as one step.
+#[derive(Debug, Default, Clone)]
+pub struct Budget {
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+    steps: u64,
+    deadline: Option<Instant>,
+}
+
+impl Budget {
+    pub fn start(&mut self) {
+        self.steps = 0;
+        self.deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+    }
+
+    pub fn tick(&mut self) -> Result<(), LoxError> {
+        self.steps += 1;
+
+        if self
+            .max_steps
+            .is_some_and(|max_steps| self.steps > max_steps)
+        {
+            return Err(LoxError::LimitExceeded {
+                message: "Execution step limit exceeded.".into(),
+            });
+        }
+
+        if self.steps % DEADLINE_CHECK_INTERVAL == 0
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(LoxError::LimitExceeded {
+                message: "Execution timed out.".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -1,24 +1,27 @@
-use std::{
-    cell::RefCell,
-    rc::Rc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::{cell::RefCell, mem, rc::Rc};
 
 use super::Interpreter;
 use crate::{
-    ast::{nodes::BlockStmt, traits::StmtVisitor},
+    ast::traits::VisitExpr,
     error::LoxError,
     interpreter::environment::Environment,
-    literal::{FunctionLiteral, Literal, NativeFunction},
+    list::LoxList,
+    literal::{FunctionLiteral, Literal},
     token::Token,
 };
 
+// Evaluated call arguments: the positional ones in order, then the named ones.
+pub struct Arguments {
+    pub positional: Vec<Literal>,
+    pub named: Vec<(Token, Literal)>,
+}
+
 pub trait LoxCallable {
     fn call(
         &self,
-        interpreter: &Interpreter,
+        interpreter: &mut Interpreter,
         token: &Token,
-        arguments: Vec<Literal>,
+        arguments: Arguments,
     ) -> Result<Literal, LoxError>;
     fn arity(&self) -> usize;
 }
@@ -26,9 +29,9 @@ pub trait LoxCallable {
 impl LoxCallable for Literal {
     fn call(
         &self,
-        interpreter: &Interpreter,
+        interpreter: &mut Interpreter,
         token: &Token,
-        arguments: Vec<Literal>,
+        arguments: Arguments,
     ) -> Result<Literal, LoxError> {
         match self {
             Literal::Function(function) => function.call(interpreter, token, arguments),
@@ -45,66 +48,168 @@ impl LoxCallable for Literal {
     }
 }
 
-// TODO: make Native Functions exten
-impl LoxCallable for NativeFunction {
-    fn call(
+impl FunctionLiteral {
+    fn execute(
         &self,
-        _interpreter: &Interpreter,
+        interpreter: &mut Interpreter,
         token: &Token,
-        arguments: Vec<Literal>,
-    ) -> Result<Literal, LoxError> {
-        if arguments.len() > self.arity() {
+        arguments: Arguments,
+    ) -> Result<Option<Literal>, LoxError> {
+        let (values, rest) = self.match_arguments(token, arguments)?;
+
+        let defer_tail_calls = mem::replace(&mut interpreter.defer_tail_calls, true);
+        let caller_script = mem::replace(&mut interpreter.script, Rc::clone(&self.script));
+        let environment = Rc::new(RefCell::new(Environment::from(&self.closure)));
+        let mut result = self
+            .bind(interpreter, &environment, values, rest)
+            .and_then(|_| interpreter.execute_scope(&self.node.body, environment));
+        interpreter.defer_tail_calls = defer_tail_calls;
+
+        // errors leaving a function defined in another file name the file they came from
+        if caller_script != self.script {
+            result = result.map_err(|error| error.in_module(&self.script));
+        }
+        interpreter.script = caller_script;
+        result
+    }
+
+    // Assigns the arguments to parameters: positional ones in order, named
+    // ones by name, and any surplus positional ones to the rest parameter.
+    // Parameters left without a value are `None`, to take their default.
+    fn match_arguments(
+        &self,
+        token: &Token,
+        arguments: Arguments,
+    ) -> Result<(Vec<Option<Literal>>, Vec<Literal>), LoxError> {
+        let params = &self.node.params;
+        let count = arguments.positional.len() + arguments.named.len();
+        let arity_error = || LoxError::Runtime {
+            token: token.clone(),
+            message: format!("Expected {} arguments got {}.", self.arity_range(), count),
+        };
+
+        let mut positional = arguments.positional.into_iter();
+        let mut values: Vec<Option<Literal>> =
+            positional.by_ref().take(params.len()).map(Some).collect();
+        values.resize(params.len(), None);
+        let rest: Vec<Literal> = positional.collect();
+        if !rest.is_empty() && self.node.rest.is_none() {
+            return Err(arity_error());
+        }
+
+        for (name, value) in arguments.named {
+            let index = params
+                .iter()
+                .position(|param| param.name.lexeme == name.lexeme)
+                .ok_or_else(|| LoxError::Runtime {
+                    token: name.clone(),
+                    message: format!("Unknown parameter '{}'.", name.lexeme),
+                })?;
+            if values[index].is_some() {
+                return Err(LoxError::Runtime {
+                    message: format!("Got more than one value for parameter '{}'.", name.lexeme),
+                    token: name,
+                });
+            }
+            values[index] = Some(value);
+        }
+
+        let missing = params
+            .iter()
+            .zip(&values)
+            .find(|(param, value)| value.is_none() && param.default.is_none());
+        if let Some((param, _)) = missing {
+            if count < self.required() {
+                return Err(arity_error());
+            }
             return Err(LoxError::Runtime {
                 token: token.clone(),
-                message: format!(
-                    "Expected {} arguments got {}.",
-                    self.arity(),
-                    arguments.len()
-                ),
+                message: format!("Missing argument for parameter '{}'.", param.name.lexeme),
             });
         }
+        Ok((values, rest))
+    }
 
-        match SystemTime::now().duration_since(UNIX_EPOCH) {
-            Ok(duration) => Ok(Literal::Number(duration.as_secs_f64().round())),
-            Err(error) => Err(LoxError::Runtime {
-                token: token.clone(),
-                message: error.to_string(),
-            }),
+    // Defines the parameters in the callee's environment, evaluating the
+    // defaults of those without a value there, so they can see earlier ones.
+    fn bind(
+        &self,
+        interpreter: &mut Interpreter,
+        environment: &Rc<RefCell<Environment>>,
+        values: Vec<Option<Literal>>,
+        rest: Vec<Literal>,
+    ) -> Result<(), LoxError> {
+        let caller_environment = mem::replace(&mut interpreter.environment, Rc::clone(environment));
+        let mut result = Ok(());
+        for (param, value) in self.node.params.iter().zip(values) {
+            let value = match (value, &param.default) {
+                (Some(value), _) => Ok(value),
+                (None, Some(default)) => default.accept(interpreter),
+                (None, None) => Ok(Literal::Nil),
+            };
+            result =
+                value.and_then(|value| environment.borrow_mut().define(param.name.clone(), value));
+            if result.is_err() {
+                break;
+            }
+        }
+        if let (Ok(()), Some(name)) = (&result, &self.node.rest) {
+            let rest = LoxList::new(&interpreter.memory, rest);
+            result = environment
+                .borrow_mut()
+                .define(name.clone(), Literal::List(Rc::new(RefCell::new(rest))));
         }
+        interpreter.environment = caller_environment;
+        result
     }
 
-    fn arity(&self) -> usize {
-        0
+    fn required(&self) -> usize {
+        self.node
+            .params
+            .iter()
+            .filter(|param| param.default.is_none())
+            .count()
+    }
+
+    // how many arguments the function accepts, for arity errors
+    fn arity_range(&self) -> String {
+        let required = self.required();
+        if self.node.rest.is_some() {
+            format!("at least {}", required)
+        } else if required == self.node.params.len() {
+            required.to_string()
+        } else {
+            format!("{} to {}", required, self.node.params.len())
+        }
     }
 }
 
 impl LoxCallable for FunctionLiteral {
     fn call(
         &self,
-        _interpreter: &Interpreter,
-        _token: &Token,
-        arguments: Vec<Literal>,
+        interpreter: &mut Interpreter,
+        token: &Token,
+        arguments: Arguments,
     ) -> Result<Literal, LoxError> {
-        let mut environment = Environment::from(&self.closure);
+        interpreter.push_frame(&self.node.name, token)?;
+        let mut result = self.execute(interpreter, token, arguments);
 
-        for (i, param) in self.node.params.iter().enumerate() {
-            if let Some(value) = arguments.get(i) {
-                environment.define(param.clone(), value.clone());
-            }
+        // Keep running calls made in tail position from this frame instead of
+        // recursing, so tail recursive functions run in constant host stack.
+        while let (Ok(_), Some(tail_call)) = (&result, interpreter.tail_call.take()) {
+            result = match tail_call.callee {
+                Literal::Function(function) => {
+                    interpreter.replace_frame(&function.node.name, &tail_call.paren);
+                    function.execute(interpreter, &tail_call.paren, tail_call.arguments)
+                }
+                callee => callee
+                    .call(interpreter, &tail_call.paren, tail_call.arguments)
+                    .map(Some),
+            };
         }
+        interpreter.frames.pop();
 
-        let block = BlockStmt {
-            statements: self.node.body.to_owned(),
-        };
-
-        let mut child_interpreter = Interpreter {
-            environment: Rc::new(RefCell::new(environment)),
-        };
-
-        if let Some(return_value) = child_interpreter.visit_block(&block)? {
-            return Ok(return_value);
-        }
-        Ok(Literal::Nil)
+        Ok(result?.unwrap_or(Literal::Nil))
     }
 
     fn arity(&self) -> usize {
@@ -1,35 +1,96 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    mem,
+    rc::Rc,
+};
 
-use crate::{error::LoxError, literal::Literal, token::Token};
+use crate::{ast::nodes::DeclarationKind, error::LoxError, literal::Literal, token::Token};
 
-#[derive(Debug, PartialEq, Clone)]
+use super::memory::Memory;
+
+#[derive(Debug)]
 pub struct Environment {
     enclosing: Option<Rc<RefCell<Environment>>>,
-    values: HashMap<String, Literal>,
+    values: HashMap<Rc<str>, Literal>,
+    // how names not declared with `var` were declared
+    kinds: HashMap<Rc<str>, DeclarationKind>,
+    // `let` bindings whose declaration hasn't run yet
+    uninitialized: HashSet<Rc<str>>,
+    memory: Rc<Memory>,
+    // bytes currently charged to `memory` by this environment
+    size: usize,
 }
 
 impl Environment {
-    pub fn new() -> Self {
+    pub fn new(memory: &Rc<Memory>) -> Self {
+        let size = Memory::environment_size();
+        memory.allocate(size);
+
         Self {
             values: HashMap::new(),
+            kinds: HashMap::new(),
+            uninitialized: HashSet::new(),
             enclosing: None,
+            memory: Rc::clone(memory),
+            size,
         }
     }
 
     pub fn from(enclosing: &Rc<RefCell<Environment>>) -> Self {
-        Self {
-            values: HashMap::new(),
-            enclosing: Some(Rc::clone(enclosing)),
+        let mut environment = Environment::new(&enclosing.borrow().memory);
+        environment.enclosing = Some(Rc::clone(enclosing));
+        environment
+    }
+
+    pub fn define(&mut self, variable: Token, value: Literal) -> Result<(), LoxError> {
+        self.declare(variable, value, DeclarationKind::Var)
+    }
+
+    // Defines a name in this environment. A constant or `let` binding can't
+    // share its scope with any other declaration of the same name.
+    pub fn declare(
+        &mut self,
+        variable: Token,
+        value: Literal,
+        kind: DeclarationKind,
+    ) -> Result<(), LoxError> {
+        let name = &variable.lexeme;
+        match self.kinds.get(name) {
+            Some(DeclarationKind::Const) => return Err(redeclared_constant(&variable)),
+            Some(_) => return Err(redeclared(&variable)),
+            None if kind == DeclarationKind::Let && self.values.contains_key(name) => {
+                return Err(redeclared(&variable))
+            }
+            // only the declaration a `let` binding was hoisted for may define it
+            None if kind != DeclarationKind::Let && self.uninitialized.contains(name) => {
+                return Err(redeclared(&variable))
+            }
+            None => {}
+        }
+
+        self.uninitialized.remove(name);
+        if kind != DeclarationKind::Var {
+            self.kinds.insert(Rc::clone(name), kind);
         }
+        self.charge(&value);
+        if let Some(previous) = self.values.insert(variable.lexeme, value) {
+            self.refund(&previous);
+        }
+        Ok(())
     }
 
-    pub fn define(&mut self, variable: Token, value: Literal) {
-        self.values.insert(variable.lexeme, value);
+    // Reserves a `let` binding before its declaration runs, so that using it
+    // earlier is an error rather than a lookup in an enclosing scope.
+    pub fn hoist(&mut self, variable: &Token) {
+        self.uninitialized.insert(Rc::clone(&variable.lexeme));
     }
 
     pub fn get(&self, variable: &Token) -> Result<Literal, LoxError> {
         if let Some(value) = self.values.get(&variable.lexeme) {
             return Ok(value.clone());
+        } else if self.uninitialized.contains(&variable.lexeme) {
+            return Err(uninitialized(variable));
         } else if let Some(parent) = &self.enclosing {
             return parent.borrow_mut().get(variable);
         }
@@ -40,9 +101,23 @@ impl Environment {
         })
     }
 
+    // a variable defined directly in this environment, ignoring enclosing ones
+    pub fn member(&self, variable: &Token) -> Option<Literal> {
+        self.values.get(&variable.lexeme).cloned()
+    }
+
     pub fn assign(&mut self, variable: &Token, value: &Literal) -> Result<Literal, LoxError> {
-        if let Some(_) = self.values.get(&variable.lexeme) {
-            self.values.insert(variable.lexeme.clone(), value.clone());
+        if self.kinds.get(&variable.lexeme) == Some(&DeclarationKind::Const) {
+            return Err(LoxError::Runtime {
+                token: variable.clone(),
+                message: format!("Cannot assign to constant '{}'.", variable.lexeme),
+            });
+        } else if self.uninitialized.contains(&variable.lexeme) {
+            return Err(uninitialized(variable));
+        } else if let Some(slot) = self.values.get_mut(&variable.lexeme) {
+            let previous = mem::replace(slot, value.clone());
+            self.charge(value);
+            self.refund(&previous);
             return Ok(value.to_owned());
         } else if let Some(parent) = &mut self.enclosing {
             return parent.borrow_mut().assign(variable, value);
@@ -54,13 +129,48 @@ impl Environment {
         })
     }
 
-    pub fn start_scope(&mut self) {
-        *self = Environment::from(&Rc::new(RefCell::new(self.clone())))
+    fn charge(&mut self, value: &Literal) {
+        let bytes = Memory::value_size(value);
+        self.size += bytes;
+        self.memory.allocate(bytes);
     }
 
-    pub fn close_scope(&mut self) {
-        if let Some(enclosing) = self.enclosing.take() {
-            *self = enclosing.borrow().clone();
-        }
+    fn refund(&mut self, value: &Literal) {
+        let bytes = Memory::value_size(value);
+        self.size -= bytes;
+        self.memory.release(bytes);
+    }
+}
+
+impl Drop for Environment {
+    fn drop(&mut self) {
+        self.memory.release(self.size);
+    }
+}
+
+fn redeclared(variable: &Token) -> LoxError {
+    LoxError::Runtime {
+        token: variable.clone(),
+        message: format!(
+            "Already a variable named '{}' in this scope.",
+            variable.lexeme
+        ),
+    }
+}
+
+fn uninitialized(variable: &Token) -> LoxError {
+    LoxError::Runtime {
+        token: variable.clone(),
+        message: format!("Cannot access '{}' before initialization.", variable.lexeme),
+    }
+}
+
+fn redeclared_constant(variable: &Token) -> LoxError {
+    LoxError::Runtime {
+        token: variable.clone(),
+        message: format!(
+            "Already a constant named '{}' in this scope.",
+            variable.lexeme
+        ),
     }
 }
@@ -0,0 +1,147 @@
+use std::rc::Rc;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{
+    callable::{Arguments, LoxCallable},
+    Interpreter,
+};
+use crate::{
+    error::LoxError,
+    literal::{ListLiteral, Literal},
+    map::MapKey,
+    token::{Token, TokenType},
+};
+
+// The items left to visit in a `for (var x in ...)` loop. Strings and maps
+// are copied when the loop starts, lists are read an index at a time so
+// pushes from the body are visited, and iterators are asked for each item.
+pub enum LoopIterator {
+    Items(std::vec::IntoIter<Literal>),
+    List(ListLiteral, usize),
+    // `None` once the end, or the last representable integer, is passed
+    Range {
+        next: Option<i64>,
+        end: i64,
+        inclusive: bool,
+    },
+    // a map whose `next` function returns each item, then nil when done
+    Iterator(Literal),
+}
+
+impl LoopIterator {
+    pub fn new(
+        interpreter: &mut Interpreter,
+        token: &Token,
+        value: Literal,
+    ) -> Result<Self, LoxError> {
+        match &value {
+            Literal::String(string) => Ok(LoopIterator::Items(
+                string
+                    .graphemes(true)
+                    .map(|grapheme| Literal::String(grapheme.into()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
+            Literal::List(list) => Ok(LoopIterator::List(Rc::clone(list), 0)),
+            // a map with an `iter` function is iterable, any other one is
+            // iterated by key
+            Literal::Map(map) => match method(&value, "iter") {
+                Some(iter) => {
+                    let iterator = iter.call(interpreter, token, no_arguments())?;
+                    if method(&iterator, "next").is_none() {
+                        return Err(error(
+                            token,
+                            "Result of 'iter' must have a 'next' function.",
+                        ));
+                    }
+                    Ok(LoopIterator::Iterator(iterator))
+                }
+                None => {
+                    let keys: Vec<Literal> = map
+                        .borrow()
+                        .iter()
+                        .map(|(key, _)| key.to_literal())
+                        .collect();
+                    Ok(LoopIterator::Items(keys.into_iter()))
+                }
+            },
+            _ => Err(error(
+                token,
+                "Can only iterate over strings, lists, maps, ranges and iterators.",
+            )),
+        }
+    }
+
+    pub fn range(operator: &Token, start: &Literal, end: &Literal) -> Result<Self, LoxError> {
+        match (start.as_integer(), end.as_integer()) {
+            (Some(start), Some(end)) => Ok(LoopIterator::Range {
+                next: Some(start),
+                end,
+                inclusive: operator.ty == TokenType::DotDotEqual,
+            }),
+            _ => Err(error(operator, "Range bounds must be integers.")),
+        }
+    }
+
+    pub fn next(
+        &mut self,
+        interpreter: &mut Interpreter,
+        token: &Token,
+    ) -> Result<Option<Literal>, LoxError> {
+        match self {
+            LoopIterator::Items(items) => Ok(items.next()),
+            LoopIterator::List(list, index) => {
+                let item = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(item)
+            }
+            LoopIterator::Range {
+                next,
+                end,
+                inclusive,
+            } => match *next {
+                Some(current) if current < *end || (*inclusive && current == *end) => {
+                    *next = current.checked_add(1);
+                    Ok(Some(Literal::Integer(current)))
+                }
+                _ => Ok(None),
+            },
+            LoopIterator::Iterator(iterator) => {
+                let next = method(iterator, "next")
+                    .ok_or_else(|| error(token, "Iterator has no 'next' function."))?;
+                match next.call(interpreter, token, no_arguments())? {
+                    Literal::Nil => Ok(None),
+                    item => Ok(Some(item)),
+                }
+            }
+        }
+    }
+}
+
+// the function a map holds under `name`, if any
+fn method(value: &Literal, name: &str) -> Option<Literal> {
+    let Literal::Map(map) = value else {
+        return None;
+    };
+    match map.borrow().get(&MapKey::String(name.into())) {
+        Some(function @ (Literal::Function(_) | Literal::NativeFunction(_))) => {
+            Some(function.clone())
+        }
+        _ => None,
+    }
+}
+
+fn no_arguments() -> Arguments {
+    Arguments {
+        positional: vec![],
+        named: vec![],
+    }
+}
+
+fn error(token: &Token, message: &str) -> LoxError {
+    LoxError::Runtime {
+        token: token.clone(),
+        message: message.into(),
+    }
+}
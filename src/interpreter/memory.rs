@@ -0,0 +1,71 @@
+use std::{cell::Cell, mem::size_of, rc::Rc};
+
+use crate::{
+    error::LoxError,
+    literal::{FunctionLiteral, Literal},
+};
+
+use super::environment::Environment;
+
+// Approximate accounting of the memory a script holds on to: environments,
+// and the strings and function objects stored in them or in lists and maps.
+// Shared by every environment, list and map an interpreter creates.
+#[derive(Debug, Default)]
+pub struct Memory {
+    current: Cell<usize>,
+    peak: Cell<usize>,
+    pub limit: Cell<Option<usize>>,
+}
+
+impl Memory {
+    pub fn new() -> Rc<Self> {
+        Rc::new(Self::default())
+    }
+
+    pub fn current(&self) -> usize {
+        self.current.get()
+    }
+
+    pub fn peak(&self) -> usize {
+        self.peak.get()
+    }
+
+    pub fn allocate(&self, bytes: usize) {
+        let current = self.current.get() + bytes;
+        self.current.set(current);
+        self.peak.set(self.peak.get().max(current));
+    }
+
+    pub fn release(&self, bytes: usize) {
+        self.current.set(self.current.get().saturating_sub(bytes));
+    }
+
+    pub fn check(&self) -> Result<(), LoxError> {
+        self.check_room(0)
+    }
+
+    // Fails if `bytes` more than are currently in use would exceed the limit.
+    pub fn check_room(&self, bytes: usize) -> Result<(), LoxError> {
+        match self.limit.get() {
+            Some(limit) if self.current().saturating_add(bytes) > limit => {
+                Err(LoxError::LimitExceeded {
+                    message: format!("Out of memory ({} byte limit).", limit),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn environment_size() -> usize {
+        size_of::<Environment>()
+    }
+
+    pub fn value_size(value: &Literal) -> usize {
+        match value {
+            Literal::String(string) => size_of::<Literal>() + string.len(),
+            Literal::BigInt(big) => size_of::<Literal>() + big.heap_size(),
+            Literal::Function(_) => size_of::<Literal>() + size_of::<FunctionLiteral>(),
+            _ => size_of::<Literal>(),
+        }
+    }
+}
@@ -1,5 +1,11 @@
+pub mod budget;
 mod callable;
-mod resolver;
+mod iteration;
+pub mod memory;
+pub mod modules;
+mod natives;
+pub mod numeric;
+pub mod resolver;
 
 pub mod visitor;
 pub use visitor::*;
@@ -0,0 +1,136 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs, iter, mem,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    ast::{nodes::Stmt, traits::StmtVisitor},
+    error::LoxError,
+    literal::Module,
+    optimizer::Optimizer,
+    parser::Parser,
+    scanner::Scanner,
+    token::Token,
+};
+
+use super::{resolver::Resolver, Interpreter};
+
+#[derive(Default)]
+pub struct Modules {
+    // directories searched for imports not found next to the importing file
+    pub search_path: Vec<PathBuf>,
+    pub opt_level: u8,
+    // keyed by canonical path, so every file is executed only once
+    loaded: HashMap<PathBuf, Rc<Module>>,
+    // files currently executing, outermost first, with the paths they were imported as
+    loading: Vec<(PathBuf, Rc<PathBuf>)>,
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl Interpreter {
+    // Marks `path` as the script being run, for relative imports and cycle detection.
+    pub fn enter_script(&mut self, path: &Path) {
+        self.script = Rc::new(path.to_path_buf());
+        self.modules
+            .loading
+            .push((canonical(path), Rc::clone(&self.script)));
+    }
+
+    pub fn import(&mut self, keyword: &Token, path: &str) -> Result<Rc<Module>, LoxError> {
+        let file = self.find_module(keyword, path)?;
+        let key = canonical(&file);
+        if let Some(module) = self.modules.loaded.get(&key) {
+            return Ok(Rc::clone(module));
+        }
+
+        let loading = &self.modules.loading;
+        if let Some(start) = loading.iter().position(|(loading, _)| *loading == key) {
+            let cycle = loading[start..]
+                .iter()
+                .map(|(_, path)| path.display().to_string())
+                .chain(iter::once(file.display().to_string()))
+                .collect::<Vec<String>>()
+                .join(" -> ");
+            return Err(LoxError::Runtime {
+                token: keyword.clone(),
+                message: format!("Import cycle: {}.", cycle),
+            });
+        }
+
+        let source = fs::read_to_string(&file).map_err(|_| LoxError::Runtime {
+            token: keyword.clone(),
+            message: format!("Could not read module '{}'.", file.display()),
+        })?;
+        let statements = self.compile(&source).map_err(|errors| LoxError::Module {
+            path: file.display().to_string(),
+            errors,
+        })?;
+
+        let module = Rc::new(Module {
+            path: Rc::new(file.clone()),
+            globals: Rc::new(RefCell::new(Interpreter::globals(&self.memory))),
+        });
+        self.modules
+            .loading
+            .push((key.clone(), Rc::clone(&module.path)));
+        let environment = mem::replace(&mut self.environment, Rc::clone(&module.globals));
+        let script = mem::replace(&mut self.script, Rc::clone(&module.path));
+        // the module's top level runs as if nothing had called it, so no call
+        // it defers is left for the importing function to run
+        let defer_tail_calls = mem::replace(&mut self.defer_tail_calls, false);
+        let tail_call = self.tail_call.take();
+        let frames = mem::take(&mut self.frames);
+        self.hoist(&statements);
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.visit_statement(statement).map(|_| ()));
+
+        self.frames = frames;
+        self.tail_call = tail_call;
+        self.defer_tail_calls = defer_tail_calls;
+        self.script = script;
+        self.environment = environment;
+        self.modules.loading.pop();
+        result.map_err(|error| error.in_module(&module.path))?;
+
+        self.modules.loaded.insert(key, Rc::clone(&module));
+        Ok(module)
+    }
+
+    // Looks for the module next to the importing file first, then along the search path.
+    fn find_module(&self, keyword: &Token, path: &str) -> Result<PathBuf, LoxError> {
+        let directory = self.script.parent().unwrap_or(Path::new(""));
+        iter::once(directory)
+            .chain(self.modules.search_path.iter().map(PathBuf::as_path))
+            .map(|directory| directory.join(path))
+            .find(|file| file.is_file())
+            .ok_or_else(|| LoxError::Runtime {
+                token: keyword.clone(),
+                message: format!("Cannot find module '{}'.", path),
+            })
+    }
+
+    fn compile(&mut self, source: &String) -> Result<Vec<Stmt>, Vec<LoxError>> {
+        let output = Scanner::new(source).run();
+        if !output.errors.is_empty() {
+            return Err(output.errors);
+        }
+
+        let mut statements = Parser::new(&output.tokens).parse()?;
+        Resolver::new()
+            .resolve_stmts(&statements)
+            .map_err(|error| vec![error])?;
+
+        if self.modules.opt_level > 0 {
+            statements = Optimizer::new().optimize(&statements);
+        }
+        Ok(statements)
+    }
+}
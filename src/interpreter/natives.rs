@@ -0,0 +1,279 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{
+    callable::{Arguments, LoxCallable},
+    Interpreter,
+};
+use crate::{
+    bigint::BigInt,
+    error::LoxError,
+    list::LoxList,
+    literal::{ListLiteral, Literal, MapLiteral, NativeFunction},
+    map::MapKey,
+    token::Token,
+};
+
+impl LoxCallable for NativeFunction {
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        token: &Token,
+        arguments: Arguments,
+    ) -> Result<Literal, LoxError> {
+        if let Some((name, _)) = arguments.named.first() {
+            return Err(error(
+                name,
+                format!(
+                    "Native function '{}' doesn't take named arguments.",
+                    self.name()
+                ),
+            ));
+        }
+        let arguments = arguments.positional;
+        if arguments.len() != self.arity() {
+            return Err(error(
+                token,
+                format!(
+                    "Expected {} arguments got {}.",
+                    self.arity(),
+                    arguments.len()
+                ),
+            ));
+        }
+
+        match self {
+            NativeFunction::Clock => match SystemTime::now().duration_since(UNIX_EPOCH) {
+                Ok(duration) => Ok(Literal::Integer(duration.as_secs_f64().round() as i64)),
+                Err(err) => Err(error(token, err.to_string())),
+            },
+            NativeFunction::Len => match &arguments[0] {
+                Literal::List(list) => Ok(Literal::Integer(list.borrow().len() as i64)),
+                Literal::Map(map) => Ok(Literal::Integer(map.borrow().len() as i64)),
+                Literal::String(string) => {
+                    Ok(Literal::Integer(string.graphemes(true).count() as i64))
+                }
+                _ => Err(error(
+                    token,
+                    "Can only get the length of lists, maps and strings.",
+                )),
+            },
+            NativeFunction::Push => {
+                let list = list_argument(token, &arguments[0])?;
+                list.borrow_mut().push(arguments[1].clone());
+                Ok(Literal::Nil)
+            }
+            NativeFunction::Pop => {
+                let list = list_argument(token, &arguments[0])?;
+                let popped = list.borrow_mut().pop();
+                popped.ok_or_else(|| error(token, "Can't pop from an empty list."))
+            }
+            NativeFunction::Insert => {
+                let list = list_argument(token, &arguments[0])?;
+                let len = list.borrow().len();
+                let index = list_index(token, &arguments[1], len + 1)?;
+                list.borrow_mut().insert(index, arguments[2].clone());
+                Ok(Literal::Nil)
+            }
+            NativeFunction::Remove => {
+                let list = list_argument(token, &arguments[0])?;
+                let len = list.borrow().len();
+                let index = list_index(token, &arguments[1], len)?;
+                let removed = list.borrow_mut().remove(index);
+                Ok(removed)
+            }
+            NativeFunction::Slice => {
+                let list = list_argument(token, &arguments[0])?;
+                let list = list.borrow();
+                let start = list_index(token, &arguments[1], list.len() + 1)?;
+                let end = list_index(token, &arguments[2], list.len() + 1)?;
+                if start > end {
+                    return Err(error(token, "Slice start must not be after its end."));
+                }
+                let slice = LoxList::new(&interpreter.memory, list[start..end].to_vec());
+                Ok(Literal::List(Rc::new(RefCell::new(slice))))
+            }
+            NativeFunction::Keys => {
+                let map = map_argument(token, &arguments[0])?;
+                let keys = map
+                    .borrow()
+                    .iter()
+                    .map(|(key, _)| key.to_literal())
+                    .collect();
+                let keys = LoxList::new(&interpreter.memory, keys);
+                Ok(Literal::List(Rc::new(RefCell::new(keys))))
+            }
+            NativeFunction::Values => {
+                let map = map_argument(token, &arguments[0])?;
+                let values = map
+                    .borrow()
+                    .iter()
+                    .map(|(_, value)| value.clone())
+                    .collect();
+                let values = LoxList::new(&interpreter.memory, values);
+                Ok(Literal::List(Rc::new(RefCell::new(values))))
+            }
+            NativeFunction::Has => {
+                let map = map_argument(token, &arguments[0])?;
+                let key = map_key(token, &arguments[1])?;
+                let has = map.borrow().contains_key(&key);
+                Ok(Literal::Boolean(has))
+            }
+            NativeFunction::Delete => {
+                let map = map_argument(token, &arguments[0])?;
+                let key = map_key(token, &arguments[1])?;
+                let removed = map.borrow_mut().remove(&key);
+                Ok(Literal::Boolean(removed.is_some()))
+            }
+            // floats are truncated towards zero, and values too wide for an
+            // i64 become bigints
+            NativeFunction::Int => match &arguments[0] {
+                Literal::Integer(_) | Literal::BigInt(_) => Ok(arguments[0].clone()),
+                Literal::Number(number) => match BigInt::from_f64(number.trunc()) {
+                    Some(big) => Ok(integer(big)),
+                    None => Err(error(
+                        token,
+                        format!("Can't convert {} to an integer.", number),
+                    )),
+                },
+                Literal::String(string) => match BigInt::parse(string.trim(), 10) {
+                    Some(big) => Ok(integer(big)),
+                    None => Err(error(
+                        token,
+                        format!("Can't convert '{}' to an integer.", string),
+                    )),
+                },
+                _ => Err(error(
+                    token,
+                    "Can only convert numbers and strings to integers.",
+                )),
+            },
+            NativeFunction::Float => match &arguments[0] {
+                Literal::Integer(integer) => Ok(Literal::Number(*integer as f64)),
+                Literal::BigInt(big) => Ok(Literal::Number(big.to_f64())),
+                Literal::Number(number) => Ok(Literal::Number(*number)),
+                Literal::String(string) => match string.trim().parse() {
+                    Ok(number) => Ok(Literal::Number(number)),
+                    Err(_) => Err(error(
+                        token,
+                        format!("Can't convert '{}' to a float.", string),
+                    )),
+                },
+                _ => Err(error(
+                    token,
+                    "Can only convert numbers and strings to floats.",
+                )),
+            },
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            NativeFunction::Clock => 0,
+            NativeFunction::Len
+            | NativeFunction::Pop
+            | NativeFunction::Keys
+            | NativeFunction::Values
+            | NativeFunction::Int
+            | NativeFunction::Float => 1,
+            NativeFunction::Push
+            | NativeFunction::Remove
+            | NativeFunction::Has
+            | NativeFunction::Delete => 2,
+            NativeFunction::Insert | NativeFunction::Slice => 3,
+        }
+    }
+}
+
+// Converts `index` into a position in a list, accepting only integers
+// below `bound`.
+fn list_index(token: &Token, index: &Literal, bound: usize) -> Result<usize, LoxError> {
+    match index.as_integer() {
+        Some(integer) => usize::try_from(integer)
+            .ok()
+            .filter(|position| *position < bound)
+            .ok_or_else(|| error(token, "List index out of range.")),
+        None => Err(error(token, "List index must be an integer.")),
+    }
+}
+
+// An integer value, using a bigint only when it doesn't fit in an i64.
+fn integer(big: BigInt) -> Literal {
+    match big.to_i64() {
+        Some(integer) => Literal::Integer(integer),
+        None => Literal::BigInt(Rc::new(big)),
+    }
+}
+
+fn list_argument(token: &Token, value: &Literal) -> Result<ListLiteral, LoxError> {
+    match value {
+        Literal::List(list) => Ok(Rc::clone(list)),
+        _ => Err(error(token, "Expected a list.")),
+    }
+}
+
+// Reads `object[index]` for a list or map.
+pub fn get_index(token: &Token, object: &Literal, index: &Literal) -> Result<Literal, LoxError> {
+    match object {
+        Literal::List(list) => {
+            let list = list.borrow();
+            let position = list_index(token, index, list.len())?;
+            Ok(list[position].clone())
+        }
+        Literal::Map(map) => {
+            let key = map_key(token, index)?;
+            match map.borrow().get(&key) {
+                Some(value) => Ok(value.clone()),
+                None => Err(error(token, format!("Undefined key '{}'.", index))),
+            }
+        }
+        _ => Err(error(token, "Only lists and maps can be indexed.")),
+    }
+}
+
+// Stores `value` at `object[index]` for a list or map, returning the value.
+pub fn set_index(
+    token: &Token,
+    object: &Literal,
+    index: &Literal,
+    value: Literal,
+) -> Result<Literal, LoxError> {
+    match object {
+        Literal::List(list) => {
+            let mut list = list.borrow_mut();
+            let position = list_index(token, index, list.len())?;
+            list.set(position, value.clone());
+            Ok(value)
+        }
+        Literal::Map(map) => {
+            let key = map_key(token, index)?;
+            map.borrow_mut().insert(key, value.clone());
+            Ok(value)
+        }
+        _ => Err(error(token, "Only lists and maps can be indexed.")),
+    }
+}
+
+pub fn map_key(token: &Token, key: &Literal) -> Result<MapKey, LoxError> {
+    MapKey::from_literal(key)
+        .ok_or_else(|| error(token, "Map keys must be strings, numbers, booleans or nil."))
+}
+
+fn map_argument(token: &Token, value: &Literal) -> Result<MapLiteral, LoxError> {
+    match value {
+        Literal::Map(map) => Ok(Rc::clone(map)),
+        _ => Err(error(token, "Expected a map.")),
+    }
+}
+
+fn error(token: &Token, message: impl Into<String>) -> LoxError {
+    LoxError::Runtime {
+        token: token.clone(),
+        message: message.into(),
+    }
+}
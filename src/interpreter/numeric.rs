@@ -0,0 +1,290 @@
+use std::{cmp::Ordering, rc::Rc};
+
+use crate::{
+    bigint::BigInt,
+    error::LoxError,
+    literal::Literal,
+    token::{Token, TokenType},
+};
+
+use super::Interpreter;
+
+// Two numeric operands brought to a common type. Integers stay exact, a
+// bigint on either side makes both bigints, and a float on either side
+// promotes both to floats.
+enum Operands {
+    Integer(i64, i64),
+    Big(BigInt, BigInt),
+    Float(f64, f64),
+}
+
+impl Operands {
+    fn new(left: &Literal, right: &Literal) -> Option<Self> {
+        match (left, right) {
+            (Literal::Integer(left), Literal::Integer(right)) => {
+                Some(Operands::Integer(*left, *right))
+            }
+            (Literal::Number(_), _) | (_, Literal::Number(_)) => {
+                Some(Operands::Float(float(left)?, float(right)?))
+            }
+            _ => Some(Operands::Big(left.to_bigint()?, right.to_bigint()?)),
+        }
+    }
+}
+
+fn float(value: &Literal) -> Option<f64> {
+    match value {
+        Literal::Integer(integer) => Some(*integer as f64),
+        Literal::BigInt(big) => Some(big.to_f64()),
+        Literal::Number(number) => Some(*number),
+        _ => None,
+    }
+}
+
+pub fn is_number(value: &Literal) -> bool {
+    matches!(
+        value,
+        Literal::Integer(_) | Literal::BigInt(_) | Literal::Number(_)
+    )
+}
+
+// Applies one of `+ - * / % ~/ **` to two numbers.
+pub fn arithmetic(
+    interpreter: &mut Interpreter,
+    operator: &Token,
+    left: &Literal,
+    right: &Literal,
+) -> Result<Literal, LoxError> {
+    match Operands::new(left, right) {
+        Some(Operands::Integer(left, right)) => {
+            integer_arithmetic(interpreter, operator, left, right)
+        }
+        Some(Operands::Big(left, right)) => big_arithmetic(interpreter, operator, left, right),
+        Some(Operands::Float(left, right)) => float_arithmetic(operator, left, right),
+        None => Err(error(operator, "Operands must be numbers.")),
+    }
+}
+
+fn integer_arithmetic(
+    interpreter: &mut Interpreter,
+    operator: &Token,
+    left: i64,
+    right: i64,
+) -> Result<Literal, LoxError> {
+    if right == 0 && matches!(operator.ty, TokenType::Percent | TokenType::TildeSlash) {
+        return Err(error(operator, "Division by zero."));
+    }
+
+    let result = match operator.ty {
+        TokenType::Plus => left.checked_add(right),
+        TokenType::Minus => left.checked_sub(right),
+        TokenType::Star => left.checked_mul(right),
+        // `/` is always true division, `~/` is the integer one
+        TokenType::Slash => return Ok(Literal::Number(left as f64 / right as f64)),
+        TokenType::Percent => {
+            let remainder = left.wrapping_rem(right);
+            if remainder != 0 && (remainder < 0) != (right < 0) {
+                Some(remainder + right)
+            } else {
+                Some(remainder)
+            }
+        }
+        TokenType::TildeSlash => left.checked_div(right).map(|quotient| {
+            if left.wrapping_rem(right) != 0 && (left < 0) != (right < 0) {
+                quotient - 1
+            } else {
+                quotient
+            }
+        }),
+        TokenType::StarStar => match u32::try_from(right) {
+            Ok(exponent) => left.checked_pow(exponent),
+            // negative powers are fractions
+            Err(_) if right < 0 => return Ok(Literal::Number((left as f64).powf(right as f64))),
+            Err(_) => None,
+        },
+        _ => return Err(error(operator, "Invalid binary expression found")),
+    };
+    match result {
+        Some(integer) => Ok(Literal::Integer(integer)),
+        // redo the operation without the 64-bit limit
+        None => big_arithmetic(
+            interpreter,
+            operator,
+            BigInt::from(left),
+            BigInt::from(right),
+        ),
+    }
+}
+
+// Multiplications count against the execution budget as they go, so a huge
+// product or power can be stopped by the step limit or the timeout.
+fn big_arithmetic(
+    interpreter: &mut Interpreter,
+    operator: &Token,
+    left: BigInt,
+    right: BigInt,
+) -> Result<Literal, LoxError> {
+    let tick = &mut || interpreter.budget.tick();
+    let result = match operator.ty {
+        TokenType::Plus => &left + &right,
+        TokenType::Minus => &left - &right,
+        TokenType::Star => left.mul_with(&right, tick)?,
+        TokenType::Slash => return Ok(Literal::Number(left.to_f64() / right.to_f64())),
+        TokenType::Percent | TokenType::TildeSlash => {
+            let (quotient, remainder) = left
+                .div_mod_floor(&right)
+                .ok_or_else(|| error(operator, "Division by zero."))?;
+            match operator.ty {
+                TokenType::Percent => remainder,
+                _ => quotient,
+            }
+        }
+        TokenType::StarStar => match right.to_i64() {
+            Some(exponent) if exponent < 0 => {
+                return Ok(Literal::Number(left.to_f64().powf(exponent as f64)))
+            }
+            exponent => {
+                let exponent = exponent
+                    .and_then(|exponent| u32::try_from(exponent).ok())
+                    .ok_or_else(|| error(operator, "Exponent is too large."))?;
+                // a base of n bits raised to `exponent` has at least
+                // exponent * (n - 1) bits, so refuse early what can't fit
+                let bits = left
+                    .bits()
+                    .saturating_sub(1)
+                    .saturating_mul(exponent as u64);
+                let bytes = usize::try_from(bits / 8).unwrap_or(usize::MAX);
+                interpreter.memory.check_room(bytes)?;
+                left.pow_with(exponent, tick)?
+            }
+        },
+        _ => return Err(error(operator, "Invalid binary expression found")),
+    };
+    Ok(Literal::BigInt(Rc::new(result)))
+}
+
+fn float_arithmetic(operator: &Token, left: f64, right: f64) -> Result<Literal, LoxError> {
+    // integer division and remainders by zero are errors whatever the operand
+    // types, only `/` follows floating point and gives infinity or NaN
+    if right == 0.0 && matches!(operator.ty, TokenType::Percent | TokenType::TildeSlash) {
+        return Err(error(operator, "Division by zero."));
+    }
+
+    let result = match operator.ty {
+        TokenType::Plus => left + right,
+        TokenType::Minus => left - right,
+        TokenType::Star => left * right,
+        TokenType::Slash => left / right,
+        // the result takes the sign of the divisor, so `-7 % 3` is 2
+        TokenType::Percent => {
+            let remainder = left % right;
+            if remainder != 0.0 && (remainder < 0.0) != (right < 0.0) {
+                remainder + right
+            } else {
+                remainder
+            }
+        }
+        TokenType::TildeSlash => (left / right).floor(),
+        TokenType::StarStar => left.powf(right),
+        _ => return Err(error(operator, "Invalid binary expression found")),
+    };
+    Ok(Literal::Number(result))
+}
+
+// Applies one of `> >= < <=` to two numbers.
+pub fn compare(operator: &Token, left: &Literal, right: &Literal) -> Result<Literal, LoxError> {
+    if !is_number(left) || !is_number(right) {
+        return Err(error(operator, "Operands must be numbers."));
+    }
+    let ordering = ordering(left, right);
+    let result = match operator.ty {
+        TokenType::Greater => ordering == Some(Ordering::Greater),
+        TokenType::GreaterEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        TokenType::Less => ordering == Some(Ordering::Less),
+        _ => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+    };
+    Ok(Literal::Boolean(result))
+}
+
+// Orders two numbers exactly, without rounding an integer to a float, so `<`
+// and friends agree with `==`. `None` if either isn't a number or is NaN.
+pub fn ordering(left: &Literal, right: &Literal) -> Option<Ordering> {
+    match (left, right) {
+        (Literal::Integer(left), Literal::Integer(right)) => Some(left.cmp(right)),
+        (Literal::Number(left), Literal::Number(right)) => left.partial_cmp(right),
+        (Literal::Integer(left), Literal::Number(right)) => mixed_ordering(*left, *right),
+        (Literal::Number(left), Literal::Integer(right)) => {
+            mixed_ordering(*right, *left).map(Ordering::reverse)
+        }
+        (Literal::BigInt(left), Literal::Number(right)) => big_float_ordering(left, *right),
+        (Literal::Number(left), Literal::BigInt(right)) => {
+            big_float_ordering(right, *left).map(Ordering::reverse)
+        }
+        // what is left are integers and bigints, or something that isn't a number
+        _ => Some(left.to_bigint()?.cmp(&right.to_bigint()?)),
+    }
+}
+
+fn mixed_ordering(integer: i64, float: f64) -> Option<Ordering> {
+    // 2^63 is exact as a float, and every integer lies in [-2^63, 2^63)
+    let bound = -(i64::MIN as f64);
+    if float.is_nan() {
+        None
+    } else if float >= bound {
+        Some(Ordering::Less)
+    } else if float < -bound {
+        Some(Ordering::Greater)
+    } else {
+        // the float's whole part converts exactly, and its fraction breaks a tie
+        let whole = float.trunc();
+        Some(
+            integer
+                .cmp(&(whole as i64))
+                .then(whole.partial_cmp(&float)?),
+        )
+    }
+}
+
+fn big_float_ordering(big: &BigInt, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        None
+    } else if float.is_infinite() {
+        Some(if float > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        })
+    } else {
+        let whole = float.trunc();
+        Some(
+            big.cmp(&BigInt::from_f64(whole)?)
+                .then(whole.partial_cmp(&float)?),
+        )
+    }
+}
+
+pub fn negate(operator: &Token, value: &Literal) -> Result<Literal, LoxError> {
+    match value {
+        Literal::Integer(integer) => Ok(match integer.checked_neg() {
+            Some(negated) => Literal::Integer(negated),
+            None => Literal::BigInt(Rc::new(-&BigInt::from(*integer))),
+        }),
+        Literal::BigInt(big) => Ok(Literal::BigInt(Rc::new(-big.as_ref()))),
+        Literal::Number(number) => Ok(Literal::Number(-number)),
+        _ => Err(error(operator, "Operand must be a number.")),
+    }
+}
+
+// Bitwise operators work on values holding an exact 64-bit integer.
+pub fn integer_operand(token: &Token, value: &Literal) -> Result<i64, LoxError> {
+    value
+        .as_integer()
+        .ok_or_else(|| error(token, "Operands must be integers."))
+}
+
+fn error(token: &Token, message: &str) -> LoxError {
+    LoxError::Runtime {
+        token: token.clone(),
+        message: message.into(),
+    }
+}
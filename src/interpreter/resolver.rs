@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, mem, rc::Rc, slice};
 
 use crate::{
     ast::{
@@ -6,211 +6,595 @@ use crate::{
         traits::{ExprVisitor, StmtVisitor, VisitExpr, VisitStmt},
     },
     error::LoxError,
-    interpreter::Interpreter,
     token::Token,
 };
 
+// What the resolver knows about a name declared in a local scope.
+#[derive(Clone, Copy)]
+pub struct Binding {
+    pub kind: DeclarationKind,
+    // false while the declaration's initializer is being resolved
+    pub defined: bool,
+}
+
 pub struct Resolver {
-    pub interpreter: Interpreter,
-    pub scopes: Vec<HashMap<String, bool>>,
+    pub scopes: Vec<HashMap<Rc<str>, Binding>>,
+    // loops enclosing the current statement, within the current function
+    loop_depth: usize,
+    // index of the outermost scope belonging to the current function
+    function_start: usize,
 }
 
 impl Resolver {
-    pub fn new(interpreter: Interpreter) -> Self {
-        return Self {
-            interpreter,
+    pub fn new() -> Self {
+        Self {
             scopes: vec![],
-        };
+            loop_depth: 0,
+            function_start: 0,
+        }
     }
 
-    pub fn begin_scope(&mut self) -> () {
+    pub fn begin_scope(&mut self) {
         self.scopes.push(HashMap::new());
     }
 
-    pub fn end_scope(&mut self) -> () {
+    pub fn end_scope(&mut self) {
         self.scopes.pop();
     }
 
-    pub fn resolve_stmt(&mut self, statement: &Stmt) -> Option<LoxError> {
+    pub fn resolve_stmt(&mut self, statement: &Stmt) -> Result<(), LoxError> {
         statement.accept(self)
     }
 
-    pub fn resolve_stmts(&mut self, statements: &Vec<Stmt>) -> Option<LoxError> {
+    pub fn resolve_stmts(&mut self, statements: &[Stmt]) -> Result<(), LoxError> {
+        for statement in statements {
+            self.resolve_stmt(statement)?;
+        }
+        Ok(())
+    }
+
+    // Resolves the statements of a new scope, declaring their `let` bindings
+    // first so that uses before the declaration don't resolve further out.
+    fn resolve_scope(&mut self, statements: &[Stmt]) -> Result<(), LoxError> {
         for statement in statements {
-            self.resolve_stmt(&statement)?;
+            let names = match statement {
+                Stmt::Variable(variable_stmt) if variable_stmt.kind == DeclarationKind::Let => {
+                    vec![&variable_stmt.token]
+                }
+                Stmt::Destructure(destructure_stmt)
+                    if destructure_stmt.kind == DeclarationKind::Let =>
+                {
+                    check_pattern(&destructure_stmt.pattern)?
+                }
+                _ => continue,
+            };
+            for name in names {
+                self.declare(name, DeclarationKind::Let)?;
+            }
         }
-        None
+        self.resolve_stmts(statements)
     }
 
-    pub fn resolve_expr(&mut self, expr: &Expr) -> Option<LoxError> {
+    // Rejects using a `let` binding of the current function before its
+    // declaration has run. Uses from nested functions are checked at runtime,
+    // since they may only run once the binding is initialized.
+    fn check_initialized(&self, token: &Token) -> Result<(), LoxError> {
+        for (index, scope) in self.scopes.iter().enumerate().rev() {
+            if let Some(binding) = scope.get(&token.lexeme) {
+                if binding.kind == DeclarationKind::Let
+                    && !binding.defined
+                    && index >= self.function_start
+                {
+                    return Err(LoxError::Parser {
+                        token: token.clone(),
+                        message: format!("Cannot access '{}' before initialization.", token.lexeme),
+                    });
+                }
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_for(&mut self, for_stmt: &ForStmt) -> Result<(), LoxError> {
+        if let Some(initializer) = &for_stmt.initializer {
+            self.resolve_scope(slice::from_ref(initializer))?;
+        }
+        if let Some(condition) = &for_stmt.condition {
+            self.resolve_expr(condition)?;
+        }
+        if let Some(increment) = &for_stmt.increment {
+            self.resolve_expr(increment)?;
+        }
+        self.resolve_loop_body(&for_stmt.body)
+    }
+
+    pub fn resolve_expr(&mut self, expr: &Expr) -> Result<(), LoxError> {
         expr.accept(self)
     }
 
-    fn define(&mut self, token: &Token) {
+    // Adds a name to the innermost scope. Constants and `let` bindings can't
+    // share their scope with any other declaration of the same name.
+    fn declare(&mut self, token: &Token, kind: DeclarationKind) -> Result<(), LoxError> {
         if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(token.lexeme.to_owned(), false);
+            match scope.get(&token.lexeme).map(|binding| binding.kind) {
+                Some(DeclarationKind::Const) => {
+                    return Err(LoxError::Parser {
+                        token: token.clone(),
+                        message: format!(
+                            "Already a constant named '{}' in this scope.",
+                            token.lexeme
+                        ),
+                    });
+                }
+                Some(DeclarationKind::Let) => return Err(already_declared(token)),
+                Some(_) if kind == DeclarationKind::Let => return Err(already_declared(token)),
+                _ => {}
+            }
+            let binding = Binding {
+                kind,
+                defined: false,
+            };
+            scope.insert(token.lexeme.to_owned(), binding);
         }
+        Ok(())
     }
 
-    fn declare(&mut self, token: &Token) {
-        if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(token.lexeme.to_owned(), true);
+    fn define(&mut self, token: &Token) {
+        if let Some(binding) = self
+            .scopes
+            .last_mut()
+            .and_then(|scope| scope.get_mut(&token.lexeme))
+        {
+            binding.defined = true;
         }
     }
 
-    fn resolve_local(&self, expr: &Expr, token: &Token) {
-        for (index, scope) in self.scopes.iter().rev().enumerate() {
-            if scope.contains_key(&token.lexeme) {
-                self.interpreter
-                    .resolve(expr, self.scopes.len() - 1 - index);
-                return;
-            }
+    // Rejects assigning to a local constant. Global constants are only known
+    // at runtime, so the environment checks those.
+    fn check_assignable(&self, token: &Token) -> Result<(), LoxError> {
+        let binding = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&token.lexeme));
+        if binding.is_some_and(|binding| binding.kind == DeclarationKind::Const) {
+            return Err(LoxError::Parser {
+                token: token.clone(),
+                message: format!("Cannot assign to constant '{}'.", token.lexeme),
+            });
         }
+        Ok(())
     }
 
-    fn resolve_function_stmt(&mut self, function_stmt: &FunctionStmt) {
+    fn resolve_function_stmt(&mut self, function_stmt: &FunctionStmt) -> Result<(), LoxError> {
+        let enclosing_loop_depth = self.loop_depth;
+        self.loop_depth = 0;
+        let enclosing_function_start = mem::replace(&mut self.function_start, self.scopes.len());
         self.begin_scope();
-        for token in &function_stmt.params {
-            self.declare(token);
-            self.define(token);
+        for param in &function_stmt.params {
+            // defaults only see the parameters before them
+            if let Some(default) = &param.default {
+                self.resolve_expr(default)?;
+            }
+            self.declare_param(&param.name)?;
         }
-        self.resolve_stmts(&function_stmt.body);
+        if let Some(rest) = &function_stmt.rest {
+            self.declare_param(rest)?;
+        }
+        let result = self.resolve_scope(&function_stmt.body);
         self.end_scope();
+        self.function_start = enclosing_function_start;
+        self.loop_depth = enclosing_loop_depth;
+        result
+    }
+
+    fn declare_param(&mut self, token: &Token) -> Result<(), LoxError> {
+        if self
+            .scopes
+            .last()
+            .is_some_and(|scope| scope.contains_key(&token.lexeme))
+        {
+            return Err(LoxError::Parser {
+                token: token.clone(),
+                message: format!("Duplicate parameter '{}'.", token.lexeme),
+            });
+        }
+        self.declare(token, DeclarationKind::Var)?;
+        self.define(token);
+        Ok(())
+    }
+
+    // Resolves the defaults of a destructuring declaration in order, defining
+    // each name once the defaults before it are resolved.
+    fn define_pattern(&mut self, pattern: &Pattern) -> Result<(), LoxError> {
+        if let Pattern::Name(name) = pattern {
+            self.define(name);
+        }
+        for element in pattern.elements() {
+            if let Some(default) = &element.default {
+                self.resolve_expr(default)?;
+            }
+            self.define_pattern(&element.target)?;
+        }
+        Ok(())
+    }
+
+    // Resolves each name of a destructuring assignment like an assignment to it.
+    fn assign_pattern(&mut self, pattern: &Pattern) -> Result<(), LoxError> {
+        if let Pattern::Name(name) = pattern {
+            self.check_initialized(name)?;
+            self.check_assignable(name)?;
+        }
+        if let Pattern::Index(index) = pattern {
+            self.resolve_expr(&index.object)?;
+            self.resolve_expr(&index.index)?;
+        }
+        for element in pattern.elements() {
+            if let Some(default) = &element.default {
+                self.resolve_expr(default)?;
+            }
+            self.assign_pattern(&element.target)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_loop_body(&mut self, body: &Stmt) -> Result<(), LoxError> {
+        self.loop_depth += 1;
+        let result = self.resolve_stmt(body);
+        self.loop_depth -= 1;
+        result
+    }
+
+    fn check_in_loop(&self, token: &Token) -> Result<(), LoxError> {
+        if self.loop_depth == 0 {
+            return Err(LoxError::Parser {
+                token: token.clone(),
+                message: format!("Can't use '{}' outside of a loop.", token.lexeme),
+            });
+        }
+        Ok(())
     }
 }
 
-impl StmtVisitor<Option<LoxError>> for Resolver {
-    fn visit_statement(&mut self, _stmt: &Stmt) -> Option<LoxError> {
-        None
+impl StmtVisitor<Result<(), LoxError>> for Resolver {
+    fn visit_statement(&mut self, stmt: &Stmt) -> Result<(), LoxError> {
+        match stmt {
+            Stmt::Block(block_stmt) => self.visit_block(block_stmt),
+            Stmt::Expression(expr_stmt) => self.visit_expression(expr_stmt),
+            Stmt::Print(print_stmt) => self.visit_print(print_stmt),
+            Stmt::Variable(variable_stmt) => self.visit_variable_declaration(variable_stmt),
+            Stmt::Destructure(destructure_stmt) => self.visit_destructure(destructure_stmt),
+            Stmt::If(if_stmt) => self.visit_if(if_stmt),
+            Stmt::While(while_stmt) => self.visit_while(while_stmt),
+            Stmt::For(for_stmt) => self.visit_for(for_stmt),
+            Stmt::ForIn(for_in_stmt) => self.visit_for_in(for_in_stmt),
+            Stmt::Function(function_stmt) => self.visit_function(function_stmt),
+            Stmt::Return(return_stmt) => self.visit_return(return_stmt),
+            Stmt::Break(break_stmt) => self.visit_break(break_stmt),
+            Stmt::Continue(continue_stmt) => self.visit_continue(continue_stmt),
+            Stmt::Throw(throw_stmt) => self.visit_throw(throw_stmt),
+            Stmt::Try(try_stmt) => self.visit_try(try_stmt),
+            Stmt::Import(import_stmt) => self.visit_import(import_stmt),
+        }
     }
 
-    fn visit_expression(&mut self, expr_stmt: &ExpressionStmt) -> Option<LoxError> {
+    fn visit_expression(&mut self, expr_stmt: &ExpressionStmt) -> Result<(), LoxError> {
         self.resolve_expr(&expr_stmt.expression)
     }
 
-    fn visit_print(&mut self, print_stmt: &PrintStmt) -> Option<LoxError> {
+    fn visit_print(&mut self, print_stmt: &PrintStmt) -> Result<(), LoxError> {
         self.resolve_expr(&print_stmt.expression)
     }
 
     fn visit_variable_declaration(
         &mut self,
         variable_stmt: &VariableDeclarationStmt,
-    ) -> Option<LoxError> {
+    ) -> Result<(), LoxError> {
         let name = &variable_stmt.token;
-        self.define(name);
+        // `let` bindings were declared when their scope began
+        if variable_stmt.kind != DeclarationKind::Let {
+            self.declare(name, variable_stmt.kind)?;
+        }
         self.resolve_expr(&variable_stmt.initializer)?;
-        self.declare(name);
-        None
+        self.define(name);
+        Ok(())
     }
 
-    fn visit_block(&mut self, block_stmt: &BlockStmt) -> Option<LoxError> {
+    fn visit_destructure(&mut self, destructure_stmt: &DestructureStmt) -> Result<(), LoxError> {
+        // every name gets its own binding, left undefined while the
+        // initializer is resolved, as for a single variable
+        let names = check_pattern(&destructure_stmt.pattern)?;
+        // `let` bindings were declared when their scope began
+        if destructure_stmt.kind != DeclarationKind::Let {
+            for name in names {
+                self.declare(name, destructure_stmt.kind)?;
+            }
+        }
+        self.resolve_expr(&destructure_stmt.initializer)?;
+        self.define_pattern(&destructure_stmt.pattern)
+    }
+
+    fn visit_block(&mut self, block_stmt: &BlockStmt) -> Result<(), LoxError> {
         self.begin_scope();
-        let result = self.resolve_stmts(&block_stmt.statements);
+        let result = self.resolve_scope(&block_stmt.statements);
         self.end_scope();
         result
     }
 
-    fn visit_if(&mut self, if_stmt: &IfStmt) -> Option<LoxError> {
-        self.resolve_expr(&if_stmt.condition);
-        self.resolve_stmt(if_stmt.then_branch.as_ref());
+    fn visit_if(&mut self, if_stmt: &IfStmt) -> Result<(), LoxError> {
+        self.resolve_expr(&if_stmt.condition)?;
+        self.resolve_stmt(if_stmt.then_branch.as_ref())?;
         if let Some(else_branch) = if_stmt.else_branch.as_ref() {
-            self.resolve_stmt(else_branch.as_ref());
+            self.resolve_stmt(else_branch.as_ref())?;
         }
-        None
+        Ok(())
     }
 
-    fn visit_while(&mut self, _while_stmt: &WhileStmt) -> Option<LoxError> {
-        self.resolve_expr(&_while_stmt.condition);
-        self.resolve_stmt(&_while_stmt.body);
-        None
+    fn visit_while(&mut self, while_stmt: &WhileStmt) -> Result<(), LoxError> {
+        self.resolve_expr(&while_stmt.condition)?;
+        self.resolve_loop_body(&while_stmt.body)
     }
 
-    fn visit_for(&mut self, for_stmt: &ForStmt) -> Option<LoxError> {
-        if let Some(initializer) = &for_stmt.initializer {
-            self.resolve_stmt(&initializer);
+    fn visit_for(&mut self, for_stmt: &ForStmt) -> Result<(), LoxError> {
+        let scoped = matches!(
+            for_stmt.initializer.as_deref(),
+            Some(Stmt::Variable(variable_stmt)) if variable_stmt.kind != DeclarationKind::Var
+        );
+        if scoped {
+            self.begin_scope();
         }
-        if let Some(condition) = &for_stmt.condition {
-            self.resolve_expr(&condition);
+        let result = self.resolve_for(for_stmt);
+        if scoped {
+            self.end_scope();
         }
-        if let Some(increment) = &for_stmt.increment {
-            self.resolve_expr(&increment);
+        result
+    }
+
+    fn visit_for_in(&mut self, for_in_stmt: &ForInStmt) -> Result<(), LoxError> {
+        match &for_in_stmt.iterable {
+            Iterable::Value(value) => self.resolve_expr(value)?,
+            Iterable::Range { start, end, .. } => {
+                self.resolve_expr(start)?;
+                self.resolve_expr(end)?;
+            }
         }
-        self.resolve_stmt(&for_stmt.body);
-        None
+
+        // the loop variable lives in a scope of its own around the body
+        self.begin_scope();
+        self.declare(&for_in_stmt.name, for_in_stmt.kind)?;
+        self.define(&for_in_stmt.name);
+        let result = self.resolve_loop_body(&for_in_stmt.body);
+        self.end_scope();
+        result
     }
 
-    fn visit_function(&mut self, function_stmt: &FunctionStmt) -> Option<LoxError> {
+    fn visit_function(&mut self, function_stmt: &FunctionStmt) -> Result<(), LoxError> {
+        self.declare(&function_stmt.name, DeclarationKind::Var)?;
         self.define(&function_stmt.name);
-        self.declare(&function_stmt.name);
 
-        self.resolve_function_stmt(function_stmt);
-        None
+        self.resolve_function_stmt(function_stmt)
+    }
+
+    fn visit_return(&mut self, return_stmt: &ReturnStmt) -> Result<(), LoxError> {
+        self.resolve_expr(&return_stmt.value)
     }
 
-    fn visit_return(&mut self, return_stmt: &ReturnStmt) -> Option<LoxError> {
-        self.resolve_expr(&return_stmt.value);
-        None
+    fn visit_break(&mut self, break_stmt: &BreakStmt) -> Result<(), LoxError> {
+        self.check_in_loop(&break_stmt.token)
+    }
+
+    fn visit_continue(&mut self, continue_stmt: &ContinueStmt) -> Result<(), LoxError> {
+        self.check_in_loop(&continue_stmt.token)
+    }
+
+    fn visit_throw(&mut self, throw_stmt: &ThrowStmt) -> Result<(), LoxError> {
+        self.resolve_expr(&throw_stmt.value)
+    }
+
+    fn visit_try(&mut self, try_stmt: &TryStmt) -> Result<(), LoxError> {
+        self.begin_scope();
+        let result = self.resolve_scope(&try_stmt.body);
+        self.end_scope();
+        result?;
+
+        if let Some(catch) = &try_stmt.catch {
+            self.begin_scope();
+            self.declare(&catch.name, DeclarationKind::Var)?;
+            self.define(&catch.name);
+            let result = self.resolve_scope(&catch.body);
+            self.end_scope();
+            result?;
+        }
+
+        if let Some(finally) = &try_stmt.finally {
+            self.begin_scope();
+            let result = self.resolve_scope(finally);
+            self.end_scope();
+            result?;
+        }
+        Ok(())
+    }
+
+    fn visit_import(&mut self, import_stmt: &ImportStmt) -> Result<(), LoxError> {
+        for name in import_stmt.alias.iter().chain(&import_stmt.names) {
+            self.declare(name, DeclarationKind::Var)?;
+            self.define(name);
+        }
+        Ok(())
     }
 }
 
-impl ExprVisitor<Option<LoxError>> for Resolver {
-    fn visit_expr(&mut self, _expr: &Expr) -> Option<LoxError> {
-        None
+impl ExprVisitor<Result<(), LoxError>> for Resolver {
+    fn visit_expr(&mut self, expr: &Expr) -> Result<(), LoxError> {
+        match expr {
+            Expr::Binary(binary) => self.visit_binary_expr(binary),
+            Expr::Grouping(grouping) => self.visit_grouping_expr(grouping),
+            Expr::Literal(lit) => self.visit_literal_expr(lit),
+            Expr::Unary(unary) => self.visit_unary_expr(unary),
+            Expr::Variable(variable) => self.visit_variable_expr(variable),
+            Expr::Assign(assign) => self.visit_assign_expr(assign),
+            Expr::DestructureAssign(destructure) => self.visit_destructure_assign_expr(destructure),
+            Expr::Logical(logical) => self.visit_logical_expr(logical),
+            Expr::Call(call) => self.visit_call_expr(call),
+            Expr::List(list) => self.visit_list_expr(list),
+            Expr::Index(index) => self.visit_index_expr(index),
+            Expr::IndexSet(index_set) => self.visit_index_set_expr(index_set),
+            Expr::Map(map) => self.visit_map_expr(map),
+            Expr::Lambda(lambda) => self.visit_lambda_expr(lambda),
+            Expr::Get(get) => self.visit_get_expr(get),
+            Expr::Interpolation(interpolation) => self.visit_interpolation_expr(interpolation),
+            Expr::OptionalChain(chain) => self.visit_optional_chain_expr(chain),
+            Expr::Update(update) => self.visit_update_expr(update),
+            Expr::Conditional(conditional) => self.visit_conditional_expr(conditional),
+        }
     }
 
-    fn visit_binary_expr(&mut self, binary_expr: &Binary) -> Option<LoxError> {
+    fn visit_binary_expr(&mut self, binary_expr: &Binary) -> Result<(), LoxError> {
         self.resolve_expr(&binary_expr.left)?;
-        self.resolve_expr(&binary_expr.right)?;
-        None
+        self.resolve_expr(&binary_expr.right)
     }
 
-    fn visit_grouping_expr(&mut self, grouping_expr: &Grouping) -> Option<LoxError> {
+    fn visit_grouping_expr(&mut self, grouping_expr: &Grouping) -> Result<(), LoxError> {
         self.resolve_expr(&grouping_expr.expression)
     }
 
-    fn visit_literal_expr(&mut self, _literal_expr: &Lit) -> Option<LoxError> {
-        None
+    fn visit_literal_expr(&mut self, _literal_expr: &Lit) -> Result<(), LoxError> {
+        Ok(())
     }
 
-    fn visit_unary_expr(&mut self, unary_expr: &Unary) -> Option<LoxError> {
+    fn visit_unary_expr(&mut self, unary_expr: &Unary) -> Result<(), LoxError> {
         self.resolve_expr(&unary_expr.right)
     }
 
-    fn visit_variable_expr(&mut self, variable_expr: &Variable) -> Option<LoxError> {
+    fn visit_variable_expr(&mut self, variable_expr: &Variable) -> Result<(), LoxError> {
+        self.check_initialized(&variable_expr.token)?;
         let name = &variable_expr.token.lexeme;
         if let Some(scope) = self.scopes.last() {
-            if !scope.get(name).unwrap_or(&true) {
-                return Some(LoxError::Parser {
+            if scope.get(name).is_some_and(|binding| !binding.defined) {
+                return Err(LoxError::Parser {
                     token: variable_expr.token.clone(),
                     message: String::from("Can't read local variable in it's own initializer"),
                 });
             }
         }
 
-        self.resolve_local(
-            &Expr::Variable(variable_expr.to_owned()),
-            &variable_expr.token,
-        );
-        None
+        Ok(())
     }
 
-    fn visit_assign_expr(&mut self, assign_expr: &Assign) -> Option<LoxError> {
-        self.resolve_expr(&assign_expr.value);
-        self.resolve_local(&Expr::Assign(assign_expr.to_owned()), &assign_expr.token);
-        None
+    fn visit_assign_expr(&mut self, assign_expr: &Assign) -> Result<(), LoxError> {
+        self.resolve_expr(&assign_expr.value)?;
+        self.check_initialized(&assign_expr.token)?;
+        self.check_assignable(&assign_expr.token)
     }
 
-    fn visit_logical_expr(&mut self, logical_expr: &Logical) -> Option<LoxError> {
+    fn visit_destructure_assign_expr(
+        &mut self,
+        destructure_expr: &DestructureAssign,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(&destructure_expr.value)?;
+        self.assign_pattern(&destructure_expr.pattern)
+    }
+
+    fn visit_logical_expr(&mut self, logical_expr: &Logical) -> Result<(), LoxError> {
         self.resolve_expr(&logical_expr.left)?;
-        self.resolve_expr(&logical_expr.right)?;
-        None
+        self.resolve_expr(&logical_expr.right)
     }
 
-    fn visit_call_expr(&mut self, call_expr: &Call) -> Option<LoxError> {
+    fn visit_call_expr(&mut self, call_expr: &Call) -> Result<(), LoxError> {
         self.resolve_expr(&call_expr.callee)?;
         for arg in &call_expr.arguments {
             self.resolve_expr(arg)?;
         }
-        None
+        for (_, arg) in &call_expr.named {
+            self.resolve_expr(arg)?;
+        }
+        Ok(())
+    }
+
+    fn visit_list_expr(&mut self, list_expr: &List) -> Result<(), LoxError> {
+        for element in &list_expr.elements {
+            self.resolve_expr(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_map_expr(&mut self, map_expr: &Map) -> Result<(), LoxError> {
+        for (key, value) in &map_expr.entries {
+            self.resolve_expr(key)?;
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_index_expr(&mut self, index_expr: &Index) -> Result<(), LoxError> {
+        self.resolve_expr(&index_expr.object)?;
+        self.resolve_expr(&index_expr.index)
+    }
+
+    fn visit_index_set_expr(&mut self, index_set_expr: &IndexSet) -> Result<(), LoxError> {
+        self.resolve_expr(&index_set_expr.object)?;
+        self.resolve_expr(&index_set_expr.index)?;
+        self.resolve_expr(&index_set_expr.value)
+    }
+
+    fn visit_lambda_expr(&mut self, lambda_expr: &Lambda) -> Result<(), LoxError> {
+        self.resolve_function_stmt(&lambda_expr.function)
+    }
+
+    fn visit_get_expr(&mut self, get_expr: &Get) -> Result<(), LoxError> {
+        self.resolve_expr(&get_expr.object)
+    }
+
+    fn visit_interpolation_expr(
+        &mut self,
+        interpolation_expr: &Interpolation,
+    ) -> Result<(), LoxError> {
+        for part in &interpolation_expr.parts {
+            self.resolve_expr(part)?;
+        }
+        Ok(())
+    }
+
+    fn visit_optional_chain_expr(&mut self, chain_expr: &OptionalChain) -> Result<(), LoxError> {
+        self.resolve_expr(&chain_expr.expression)
+    }
+
+    fn visit_update_expr(&mut self, update_expr: &Update) -> Result<(), LoxError> {
+        if let Expr::Variable(variable) = update_expr.target.as_ref() {
+            self.check_assignable(&variable.token)?;
+        }
+        self.resolve_expr(&update_expr.target)?;
+        self.resolve_expr(&update_expr.value)
+    }
+
+    fn visit_conditional_expr(&mut self, conditional_expr: &Conditional) -> Result<(), LoxError> {
+        self.resolve_expr(&conditional_expr.condition)?;
+        self.resolve_expr(&conditional_expr.then_branch)?;
+        self.resolve_expr(&conditional_expr.else_branch)
+    }
+}
+
+// The names a declaration's pattern binds, which must all differ.
+fn check_pattern(pattern: &Pattern) -> Result<Vec<&Token>, LoxError> {
+    let names = pattern.names();
+    for (index, name) in names.iter().enumerate() {
+        if names[..index]
+            .iter()
+            .any(|earlier| earlier.lexeme == name.lexeme)
+        {
+            return Err(LoxError::Parser {
+                token: (*name).clone(),
+                message: format!("Duplicate name '{}' in pattern.", name.lexeme),
+            });
+        }
+    }
+    Ok(names)
+}
+
+fn already_declared(token: &Token) -> LoxError {
+    LoxError::Parser {
+        token: token.clone(),
+        message: format!("Already a variable named '{}' in this scope.", token.lexeme),
     }
 }
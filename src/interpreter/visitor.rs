@@ -1,55 +1,360 @@
 use std::{
     cell::{RefCell, RefMut},
-    collections::HashMap,
+    mem,
+    path::PathBuf,
+    ptr,
     rc::Rc,
 };
 
 use crate::{
     ast::{
         nodes::{
-            Assign, Binary, BlockStmt, Call, Expr, ExpressionStmt, ForStmt, FunctionStmt, Grouping,
-            IfStmt, Lit, Logical, PrintStmt, ReturnStmt, Stmt, Unary, Variable,
-            VariableDeclarationStmt, WhileStmt,
+            Assign, Binary, BlockStmt, BreakStmt, Call, Conditional, ContinueStmt, DeclarationKind,
+            DestructureAssign, DestructureStmt, Expr, ExpressionStmt, ForInStmt, ForStmt,
+            FunctionStmt, Get, Grouping, IfStmt, ImportStmt, Index, IndexSet, Interpolation,
+            Iterable, Lambda, List, Lit, Logical, Map, OptionalChain, Pattern, PrintStmt,
+            ReturnStmt, Stmt, ThrowStmt, TryStmt, Unary, Update, Variable, VariableDeclarationStmt,
+            WhileStmt,
         },
         traits::{ExprVisitor, StmtVisitor, VisitExpr, VisitStmt},
     },
     error::LoxError,
+    list::LoxList,
     literal::{FunctionLiteral, Literal, NativeFunction},
+    map::{LoxMap, MapKey},
     token::{Token, TokenType},
 };
 
-use super::{callable::LoxCallable, environment::Environment};
+use super::{
+    budget::Budget,
+    callable::{Arguments, LoxCallable},
+    environment::Environment,
+    iteration::LoopIterator,
+    memory::Memory,
+    modules::Modules,
+    natives::{get_index, map_key, set_index},
+    numeric,
+};
+
+// An address on the current stack frame, to measure how deep the host stack
+// has grown between two points.
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    ptr::addr_of!(marker) as usize
+}
+
+// A call found in tail position (`return f(...)`), evaluated up to the point
+// of invoking it. The calling function runs it once its own frame is gone.
+pub struct TailCall {
+    pub callee: Literal,
+    pub paren: Token,
+    pub arguments: Arguments,
+}
+
+// Set by `break` and `continue`, which unwind to the innermost loop the same
+// way a return unwinds to its function.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LoopControl {
+    Break,
+    Continue,
+}
+
+// A Lox function currently executing, and the line it was called from.
+pub struct CallFrame {
+    pub name: Rc<str>,
+    pub line: usize,
+}
+
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 4096;
+// Host stack that calls may use below `interpret`, leaving room to spare in
+// the 2MB a spawned thread gets by default. Hosts running the interpreter on
+// a bigger stack can raise it to allow deeper recursion.
+pub const DEFAULT_MAX_STACK_BYTES: usize = 1024 * 1024;
 
 pub struct Interpreter {
     pub environment: Rc<RefCell<Environment>>,
-    pub locals: HashMap<Expr, usize>,
+    pub frames: Vec<CallFrame>,
+    pub max_call_depth: usize,
+    pub max_stack_bytes: usize,
+    // address of a local in `interpret`, where the stack usage is measured from
+    stack_base: usize,
+    pub budget: Budget,
+    pub memory: Rc<Memory>,
+    pub tail_call: Option<TailCall>,
+    // Whether a `return f()` here can leave `f` for the running function body
+    // to call once it has returned. Only function bodies run deferred calls,
+    // and not from inside a try statement, which would be left before the
+    // call ran past its catch and finally.
+    pub defer_tail_calls: bool,
+    pub loop_control: Option<LoopControl>,
+    // the file whose code is currently running
+    pub script: Rc<PathBuf>,
+    pub modules: Modules,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        let mut globals = Environment::new();
-        globals.define(
-            Token::new(TokenType::Identifier, None, Some("clock".into()), 0.into()),
-            Literal::NativeFunction(NativeFunction::Clock),
-        );
+        let memory = Memory::new();
+        let globals = Interpreter::globals(&memory);
 
         Interpreter {
             environment: Rc::new(RefCell::new(globals)),
-            locals: HashMap::new(),
+            frames: vec![],
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            max_stack_bytes: DEFAULT_MAX_STACK_BYTES,
+            stack_base: 0,
+            budget: Budget::default(),
+            memory,
+            tail_call: None,
+            defer_tail_calls: false,
+            loop_control: None,
+            script: Rc::new(PathBuf::new()),
+            modules: Modules::default(),
         }
     }
 
+    // a fresh global environment holding the native functions
+    pub fn globals(memory: &Rc<Memory>) -> Environment {
+        let mut globals = Environment::new(memory);
+        for native in NativeFunction::ALL {
+            globals
+                .define(
+                    Token::new(
+                        TokenType::Identifier,
+                        None,
+                        Some(native.name().into()),
+                        0.into(),
+                    ),
+                    Literal::NativeFunction(native),
+                )
+                .expect("natives have distinct names");
+        }
+        globals
+    }
+
     fn get_environment(&mut self) -> RefMut<'_, Environment> {
         self.environment.borrow_mut()
     }
 
     pub fn interpret(&mut self, statements: &Vec<Stmt>) -> Result<Option<Literal>, LoxError> {
+        self.budget.start();
+        self.stack_base = stack_address();
+        self.hoist(statements);
         for statement in statements {
             self.visit_statement(statement)?;
         }
         Ok(None)
     }
 
+    pub fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        environment: Environment,
+    ) -> Result<Option<Literal>, LoxError> {
+        self.execute_scope(statements, Rc::new(RefCell::new(environment)))
+    }
+
+    pub fn execute_scope(
+        &mut self,
+        statements: &[Stmt],
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<Option<Literal>, LoxError> {
+        let previous = mem::replace(&mut self.environment, environment);
+        self.hoist(statements);
+        let mut result = Ok(None);
+        for statement in statements {
+            result = statement.accept(self);
+            if !matches!(result, Ok(None)) {
+                break;
+            }
+        }
+        self.environment = previous;
+        result
+    }
+
+    fn arguments(&mut self, call_expr: &Call) -> Result<Arguments, LoxError> {
+        let mut positional = vec![];
+        for argument in &call_expr.arguments {
+            positional.push(argument.accept(self)?);
+        }
+        let mut named = vec![];
+        for (name, argument) in &call_expr.named {
+            named.push((name.clone(), argument.accept(self)?));
+        }
+        Ok(Arguments { positional, named })
+    }
+
+    // reserves the `let` bindings declared directly in `statements`
+    pub fn hoist(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            match statement {
+                Stmt::Variable(variable_stmt) if variable_stmt.kind == DeclarationKind::Let => {
+                    self.get_environment().hoist(&variable_stmt.token);
+                }
+                Stmt::Destructure(destructure_stmt)
+                    if destructure_stmt.kind == DeclarationKind::Let =>
+                {
+                    for name in destructure_stmt.pattern.names() {
+                        self.get_environment().hoist(name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Runs a for loop from its first condition check. `binding` is the loop's
+    // own `let` or `const` declaration, copied into a new environment inside
+    // `enclosing` before each increment so closures keep their iteration's value.
+    fn run_for(
+        &mut self,
+        for_stmt: &ForStmt,
+        binding: Option<(&VariableDeclarationStmt, &Rc<RefCell<Environment>>)>,
+    ) -> Result<Option<Literal>, LoxError> {
+        loop {
+            self.step()?;
+            let condition_value = match &for_stmt.condition {
+                Some(condition_expr) => {
+                    let value = condition_expr.accept(self)?;
+                    match self.is_truthy(value, false) {
+                        Ok(Literal::Boolean(value)) => value,
+                        _ => false,
+                    }
+                }
+                None => true,
+            };
+
+            if !condition_value {
+                break;
+            }
+
+            let result = for_stmt.body.accept(self)?;
+            match self.loop_control.take() {
+                Some(LoopControl::Break) => break,
+                // the increment still runs before the next iteration
+                Some(LoopControl::Continue) => {}
+                None if result.is_some() => return Ok(result),
+                None => {}
+            }
+            if let Some((variable_stmt, enclosing)) = binding {
+                let value = self.get_environment().get(&variable_stmt.token)?;
+                let mut environment = Environment::from(enclosing);
+                environment.declare(variable_stmt.token.clone(), value, variable_stmt.kind)?;
+                self.environment = Rc::new(RefCell::new(environment));
+            }
+            if let Some(expr) = &for_stmt.increment {
+                expr.accept(self)?;
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Binds the parts of `value` matched by `pattern`, declaring the names as
+    // `kind`, or assigning to existing variables when there is no kind.
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: Literal,
+        kind: Option<DeclarationKind>,
+    ) -> Result<(), LoxError> {
+        let parts: Vec<_> = match (pattern, &value) {
+            (Pattern::Name(name), _) => {
+                match kind {
+                    Some(kind) => self.get_environment().declare(name.clone(), value, kind)?,
+                    None => {
+                        self.get_environment().assign(name, &value)?;
+                    }
+                }
+                return Ok(());
+            }
+            (Pattern::Index(target), _) => {
+                let object = target.object.accept(self)?;
+                let index = target.index.accept(self)?;
+                set_index(&target.bracket, &object, &index, value)?;
+                return Ok(());
+            }
+            (Pattern::List { elements, .. }, Literal::List(list)) => {
+                let list = list.borrow();
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, element)| (element, list.get(index).cloned()))
+                    .collect()
+            }
+            (Pattern::Map { entries, .. }, Literal::Map(map)) => {
+                let map = map.borrow();
+                entries
+                    .iter()
+                    .map(|(key, element)| {
+                        let key = MapKey::String(Rc::clone(&key.lexeme));
+                        (element, map.get(&key).cloned())
+                    })
+                    .collect()
+            }
+            (Pattern::List { bracket, .. }, _) => {
+                return Err(LoxError::Runtime {
+                    token: bracket.clone(),
+                    message: "Only lists can be destructured with '[...]'.".into(),
+                })
+            }
+            (Pattern::Map { brace, .. }, _) => {
+                return Err(LoxError::Runtime {
+                    token: brace.clone(),
+                    message: "Only maps can be destructured with '{...}'.".into(),
+                })
+            }
+        };
+
+        for (element, part) in parts {
+            let part = match (part, &element.default) {
+                (None | Some(Literal::Nil), Some(default)) => default.accept(self)?,
+                (part, _) => part.unwrap_or(Literal::Nil),
+            };
+            self.destructure(&element.target, part, kind)?;
+        }
+        Ok(())
+    }
+
+    // counts one unit of work against the execution budget and memory limit
+    fn step(&mut self) -> Result<(), LoxError> {
+        self.budget.tick()?;
+        self.memory.check()
+    }
+
+    pub fn push_frame(&mut self, name: &Token, call_site: &Token) -> Result<(), LoxError> {
+        let stack_used = self.stack_base.abs_diff(stack_address());
+        if self.frames.len() >= self.max_call_depth || stack_used > self.max_stack_bytes {
+            return Err(LoxError::StackOverflow {
+                token: call_site.clone(),
+                trace: self.stack_trace(),
+            });
+        }
+
+        self.frames.push(CallFrame {
+            name: Rc::clone(&name.lexeme),
+            line: call_site.line,
+        });
+        Ok(())
+    }
+
+    // a tail call reuses the frame of the function it returns from
+    pub fn replace_frame(&mut self, name: &Token, call_site: &Token) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.name = Rc::clone(&name.lexeme);
+            frame.line = call_site.line;
+        }
+    }
+
+    fn stack_trace(&self) -> Vec<String> {
+        self.frames
+            .iter()
+            .rev()
+            .map(|frame| format!("[line {}] in {}()", frame.line, frame.name))
+            .collect()
+    }
+
     fn are_equal(&mut self, x: Literal, y: Literal, invert: bool) -> Result<Literal, LoxError> {
         Ok(self.get_boolean_literal(x == y, invert))
     }
@@ -65,31 +370,123 @@ impl Interpreter {
     fn is_truthy(&mut self, value: Literal, invert: bool) -> Result<Literal, LoxError> {
         match value {
             Literal::String(_string) => Ok(self.get_boolean_literal(true, invert)),
+            Literal::Integer(integer) => Ok(self.get_boolean_literal(integer != 0, invert)),
+            Literal::BigInt(big) => Ok(self.get_boolean_literal(!big.is_zero(), invert)),
             Literal::Number(number) => Ok(self.get_boolean_literal(number != 0.0, invert)),
             Literal::Boolean(boolean) => Ok(self.get_boolean_literal(boolean, invert)),
             Literal::NativeFunction(_) => Ok(Literal::Boolean(false)),
             Literal::Function(_) => Ok(Literal::Boolean(false)),
+            Literal::List(_) | Literal::Map(_) | Literal::Module(_) => {
+                Ok(self.get_boolean_literal(true, invert))
+            }
             Literal::Nil => Ok(self.get_boolean_literal(false, invert)),
         }
     }
 
-    pub fn resolve(&mut self, expr: &Expr, index: usize) {
-        self.locals.insert(expr.clone(), index);
+    // applies the binary operator `operator` to two evaluated operands
+    fn binary(
+        &mut self,
+        operator: &Token,
+        left_result: Literal,
+        right_result: Literal,
+    ) -> Result<Literal, LoxError> {
+        match operator.ty {
+            TokenType::Comma => Ok(right_result.clone()),
+            TokenType::Plus => match (&left_result, &right_result) {
+                (Literal::String(left), Literal::String(right)) => {
+                    Ok(Literal::String(format!("{}{}", left, right).into()))
+                }
+                (left, right) if numeric::is_number(left) && numeric::is_number(right) => {
+                    numeric::arithmetic(self, operator, left, right)
+                }
+                (_, _) => Err(LoxError::Runtime {
+                    token: operator.clone(),
+                    message: "Operands must be two numbers or two strings.".into(),
+                }),
+            },
+            TokenType::Minus
+            | TokenType::Slash
+            | TokenType::Star
+            | TokenType::Percent
+            | TokenType::TildeSlash
+            | TokenType::StarStar => {
+                numeric::arithmetic(self, operator, &left_result, &right_result)
+            }
+            TokenType::Ampersand
+            | TokenType::Pipe
+            | TokenType::Caret
+            | TokenType::LessLess
+            | TokenType::GreaterGreater => {
+                let left = numeric::integer_operand(operator, &left_result)?;
+                let right = numeric::integer_operand(operator, &right_result)?;
+                let result = match operator.ty {
+                    TokenType::Ampersand => left & right,
+                    TokenType::Pipe => left | right,
+                    TokenType::Caret => left ^ right,
+                    _ => {
+                        let shift = u32::try_from(right)
+                            .ok()
+                            .filter(|shift| *shift < i64::BITS)
+                            .ok_or_else(|| LoxError::Runtime {
+                                token: operator.clone(),
+                                message: "Shift amount must be between 0 and 63.".into(),
+                            })?;
+                        match operator.ty {
+                            // shifting bits out, or into the sign, is an
+                            // overflow, since bitwise results stay in 64 bits
+                            TokenType::LessLess if (left << shift) >> shift != left => {
+                                return Err(LoxError::Runtime {
+                                    token: operator.clone(),
+                                    message: "Shift result doesn't fit in 64 bits.".into(),
+                                })
+                            }
+                            TokenType::LessLess => left << shift,
+                            _ => left >> shift,
+                        }
+                    }
+                };
+                Ok(Literal::Integer(result))
+            }
+            TokenType::BangEqual => self.are_equal(left_result, right_result, true),
+            TokenType::EqualEqual => self.are_equal(left_result, right_result, false),
+            TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Less
+            | TokenType::LessEqual => numeric::compare(operator, &left_result, &right_result),
+            _ => Err(LoxError::Runtime {
+                token: operator.clone(),
+                message: "Invalid binary expression found".into(),
+            }),
+        }
+    }
+
+    // the value an update expression stores in place of `old`
+    fn updated(&mut self, update_expr: &Update, old: &Literal) -> Result<Literal, LoxError> {
+        let value = update_expr.value.accept(self)?;
+        self.binary(&update_expr.operator, old.clone(), value)
     }
 }
 
 impl StmtVisitor<Result<Option<Literal>, LoxError>> for Interpreter {
     fn visit_statement(&mut self, stmt: &Stmt) -> Result<Option<Literal>, LoxError> {
+        self.step()?;
         match stmt {
             Stmt::Print(print_stmt) => self.visit_print(print_stmt),
             Stmt::Expression(expr_stmt) => self.visit_expression(expr_stmt),
             Stmt::Variable(variable_stmt) => self.visit_variable_declaration(variable_stmt),
+            Stmt::Destructure(destructure_stmt) => self.visit_destructure(destructure_stmt),
             Stmt::Block(block_stmt) => self.visit_block(block_stmt),
             Stmt::If(if_stmt) => self.visit_if(if_stmt),
             Stmt::While(while_stmt) => self.visit_while(while_stmt),
             Stmt::For(for_stmt) => self.visit_for(for_stmt),
+            Stmt::ForIn(for_in_stmt) => self.visit_for_in(for_in_stmt),
             Stmt::Function(function_stmt) => self.visit_function(function_stmt),
             Stmt::Return(return_stmt) => return_stmt.accept(self),
+            Stmt::Break(break_stmt) => self.visit_break(break_stmt),
+            Stmt::Continue(continue_stmt) => self.visit_continue(continue_stmt),
+            Stmt::Throw(throw_stmt) => self.visit_throw(throw_stmt),
+            Stmt::Try(try_stmt) => self.visit_try(try_stmt),
+            Stmt::Import(import_stmt) => self.visit_import(import_stmt),
         }
     }
 
@@ -112,22 +509,26 @@ impl StmtVisitor<Result<Option<Literal>, LoxError>> for Interpreter {
     ) -> Result<Option<Literal>, LoxError> {
         let result = self.visit_expr(&variable_stmt.initializer)?;
         self.get_environment()
-            .define(variable_stmt.token.clone(), result);
+            .declare(variable_stmt.token.clone(), result, variable_stmt.kind)?;
+        Ok(None)
+    }
+
+    fn visit_destructure(
+        &mut self,
+        destructure_stmt: &DestructureStmt,
+    ) -> Result<Option<Literal>, LoxError> {
+        let value = self.visit_expr(&destructure_stmt.initializer)?;
+        self.destructure(
+            &destructure_stmt.pattern,
+            value,
+            Some(destructure_stmt.kind),
+        )?;
         Ok(None)
     }
 
     fn visit_block(&mut self, block_stmt: &BlockStmt) -> Result<Option<Literal>, LoxError> {
-        let mut return_value = None;
-        self.get_environment().start_scope();
-        for statement in &block_stmt.statements {
-            return_value = statement.accept(self)?;
-            if return_value.is_some() {
-                self.get_environment().close_scope();
-                return Ok(return_value);
-            }
-        }
-        self.get_environment().close_scope();
-        Ok(return_value)
+        let environment = Environment::from(&self.environment);
+        self.execute_block(&block_stmt.statements, environment)
     }
 
     fn visit_if(&mut self, if_stmt: &IfStmt) -> Result<Option<Literal>, LoxError> {
@@ -137,10 +538,11 @@ impl StmtVisitor<Result<Option<Literal>, LoxError>> for Interpreter {
                 true => {
                     return if_stmt.then_branch.accept(self);
                 }
-                false => match &if_stmt.else_branch {
-                    Some(statement) => return statement.accept(self),
-                    None => {}
-                },
+                false => {
+                    if let Some(statement) = &if_stmt.else_branch {
+                        return statement.accept(self);
+                    }
+                }
             }
             return Ok(None);
         }
@@ -150,12 +552,16 @@ impl StmtVisitor<Result<Option<Literal>, LoxError>> for Interpreter {
 
     fn visit_while(&mut self, while_stmt: &WhileStmt) -> Result<Option<Literal>, LoxError> {
         loop {
+            self.step()?;
             let condition = while_stmt.condition.accept(self)?;
             if let Literal::Boolean(is_true) = self.is_truthy(condition, false)? {
                 if is_true {
                     let result = while_stmt.body.accept(self)?;
-                    if result.is_some() {
-                        return Ok(result);
+                    match self.loop_control.take() {
+                        Some(LoopControl::Break) => break,
+                        Some(LoopControl::Continue) => continue,
+                        None if result.is_some() => return Ok(result),
+                        None => {}
                     }
                 } else {
                     break;
@@ -167,34 +573,58 @@ impl StmtVisitor<Result<Option<Literal>, LoxError>> for Interpreter {
     }
 
     fn visit_for(&mut self, for_stmt: &ForStmt) -> Result<Option<Literal>, LoxError> {
-        if let Some(initializer) = &for_stmt.initializer {
-            initializer.accept(self)?;
+        match for_stmt.initializer.as_deref() {
+            // `let` and `const` bindings belong to the loop, with a fresh copy
+            // of them for every iteration
+            Some(Stmt::Variable(variable_stmt)) if variable_stmt.kind != DeclarationKind::Var => {
+                let enclosing = Rc::clone(&self.environment);
+                self.environment = Rc::new(RefCell::new(Environment::from(&enclosing)));
+                let result = self
+                    .visit_variable_declaration(variable_stmt)
+                    .and_then(|_| self.run_for(for_stmt, Some((variable_stmt, &enclosing))));
+                self.environment = enclosing;
+                result
+            }
+            Some(initializer) => {
+                initializer.accept(self)?;
+                self.run_for(for_stmt, None)
+            }
+            None => self.run_for(for_stmt, None),
         }
+    }
 
-        loop {
-            let condition_value = match &for_stmt.condition {
-                Some(condition_expr) => {
-                    let value = condition_expr.accept(self)?;
-                    match self.is_truthy(value, false) {
-                        Ok(Literal::Boolean(value)) => value,
-                        _ => false,
-                    }
-                }
-                None => true,
-            };
+    fn visit_for_in(&mut self, for_in_stmt: &ForInStmt) -> Result<Option<Literal>, LoxError> {
+        let mut iterator = match &for_in_stmt.iterable {
+            Iterable::Value(value) => {
+                let value = value.accept(self)?;
+                LoopIterator::new(self, &for_in_stmt.keyword, value)?
+            }
+            Iterable::Range {
+                start,
+                operator,
+                end,
+            } => {
+                let start = start.accept(self)?;
+                let end = end.accept(self)?;
+                LoopIterator::range(operator, &start, &end)?
+            }
+        };
 
-            if !condition_value {
+        loop {
+            self.step()?;
+            let Some(item) = iterator.next(self, &for_in_stmt.keyword)? else {
                 break;
-            }
+            };
 
-            let result = for_stmt.body.accept(self)?;
-            if result.is_some() {
-                return Ok(result);
-            }
-            match &for_stmt.increment {
-                Some(expr) => {
-                    expr.accept(self)?;
-                }
+            // a new environment per item, so closures in the body keep their own
+            let mut environment = Environment::from(&self.environment);
+            environment.declare(for_in_stmt.name.clone(), item, for_in_stmt.kind)?;
+            let result =
+                self.execute_block(std::slice::from_ref(for_in_stmt.body.as_ref()), environment)?;
+            match self.loop_control.take() {
+                Some(LoopControl::Break) => break,
+                Some(LoopControl::Continue) => {}
+                None if result.is_some() => return Ok(result),
                 None => {}
             }
         }
@@ -208,23 +638,128 @@ impl StmtVisitor<Result<Option<Literal>, LoxError>> for Interpreter {
     ) -> Result<Option<Literal>, LoxError> {
         let function = function_stmt.clone();
         let function_literal = Literal::Function(FunctionLiteral {
-            node: function,
+            node: Rc::new(function),
             closure: Rc::clone(&self.environment),
+            script: Rc::clone(&self.script),
         });
 
         self.get_environment()
-            .define(*(function_stmt.name).clone(), function_literal);
+            .define(*(function_stmt.name).clone(), function_literal)?;
 
         Ok(None)
     }
 
     fn visit_return(&mut self, return_stmt: &ReturnStmt) -> Result<Option<Literal>, LoxError> {
+        if let (Expr::Call(call_expr), true) = (&return_stmt.value, self.defer_tail_calls) {
+            let callee = call_expr.callee.accept(self)?;
+            let arguments = self.arguments(call_expr)?;
+
+            self.tail_call = Some(TailCall {
+                callee,
+                paren: call_expr.paren.clone(),
+                arguments,
+            });
+            return Ok(Some(Literal::Nil));
+        }
+
         Ok(Some(return_stmt.value.accept(self)?))
     }
+
+    fn visit_break(&mut self, _break_stmt: &BreakStmt) -> Result<Option<Literal>, LoxError> {
+        self.loop_control = Some(LoopControl::Break);
+        Ok(Some(Literal::Nil))
+    }
+
+    fn visit_continue(
+        &mut self,
+        _continue_stmt: &ContinueStmt,
+    ) -> Result<Option<Literal>, LoxError> {
+        self.loop_control = Some(LoopControl::Continue);
+        Ok(Some(Literal::Nil))
+    }
+
+    fn visit_throw(&mut self, throw_stmt: &ThrowStmt) -> Result<Option<Literal>, LoxError> {
+        Err(LoxError::Thrown {
+            token: throw_stmt.token.clone(),
+            value: Box::new(throw_stmt.value.accept(self)?),
+        })
+    }
+
+    fn visit_try(&mut self, try_stmt: &TryStmt) -> Result<Option<Literal>, LoxError> {
+        let defer_tail_calls = mem::replace(&mut self.defer_tail_calls, false);
+        let environment = Environment::from(&self.environment);
+        let mut result = self.execute_block(&try_stmt.body, environment);
+
+        if let (Err(error), Some(catch)) = (&result, &try_stmt.catch) {
+            if let Some(value) = error_value(error, &self.memory) {
+                let mut environment = Environment::from(&self.environment);
+                environment.define(catch.name.clone(), value)?;
+                result = self.execute_block(&catch.body, environment);
+            }
+        }
+
+        if let Some(finally) = &try_stmt.finally {
+            // a pending break or continue resumes unless finally jumps itself
+            let loop_control = self.loop_control.take();
+            let environment = Environment::from(&self.environment);
+            match self.execute_block(finally, environment) {
+                Ok(None) => self.loop_control = loop_control,
+                jump => result = jump,
+            }
+        }
+        self.defer_tail_calls = defer_tail_calls;
+
+        result
+    }
+
+    fn visit_import(&mut self, import_stmt: &ImportStmt) -> Result<Option<Literal>, LoxError> {
+        let path = import_stmt.path.literal.to_string();
+        let module = self.import(&import_stmt.keyword, &path)?;
+
+        for name in &import_stmt.names {
+            let value = module
+                .globals
+                .borrow()
+                .member(name)
+                .ok_or_else(|| LoxError::Runtime {
+                    token: name.clone(),
+                    message: format!("Module '{}' has no member '{}'.", path, name.lexeme),
+                })?;
+            self.get_environment().define(name.clone(), value)?;
+        }
+        if let Some(alias) = &import_stmt.alias {
+            self.get_environment()
+                .define(alias.clone(), Literal::Module(module))?;
+        }
+        Ok(None)
+    }
+}
+
+// The value a catch clause binds for an error, if the error can be caught.
+// Runtime errors become maps holding their message and line.
+fn error_value(error: &LoxError, memory: &Rc<Memory>) -> Option<Literal> {
+    match error {
+        LoxError::Thrown { value, .. } => Some(*value.clone()),
+        LoxError::Module { errors, .. } if errors.len() == 1 => error_value(&errors[0], memory),
+        LoxError::Runtime { token, message } => {
+            let mut map = LoxMap::new(memory);
+            map.insert(
+                MapKey::String("message".into()),
+                Literal::String(message.as_str().into()),
+            );
+            map.insert(
+                MapKey::String("line".into()),
+                Literal::Integer(token.line as i64),
+            );
+            Some(Literal::Map(Rc::new(RefCell::new(map))))
+        }
+        _ => None,
+    }
 }
 
 impl ExprVisitor<Result<Literal, LoxError>> for Interpreter {
     fn visit_expr(&mut self, expr: &Expr) -> Result<Literal, LoxError> {
+        self.step()?;
         match expr {
             Expr::Binary(binary) => self.visit_binary_expr(binary),
             Expr::Grouping(grouping) => self.visit_grouping_expr(grouping),
@@ -232,8 +767,19 @@ impl ExprVisitor<Result<Literal, LoxError>> for Interpreter {
             Expr::Unary(unary) => self.visit_unary_expr(unary),
             Expr::Variable(variable) => self.visit_variable_expr(variable),
             Expr::Assign(assign) => self.visit_assign_expr(assign),
+            Expr::DestructureAssign(destructure) => self.visit_destructure_assign_expr(destructure),
             Expr::Logical(logical) => self.visit_logical_expr(logical),
             Expr::Call(call) => self.visit_call_expr(call),
+            Expr::List(list) => self.visit_list_expr(list),
+            Expr::Index(index) => self.visit_index_expr(index),
+            Expr::IndexSet(index_set) => self.visit_index_set_expr(index_set),
+            Expr::Map(map) => self.visit_map_expr(map),
+            Expr::Lambda(lambda) => self.visit_lambda_expr(lambda),
+            Expr::Get(get) => self.visit_get_expr(get),
+            Expr::Interpolation(interpolation) => self.visit_interpolation_expr(interpolation),
+            Expr::OptionalChain(chain) => self.visit_optional_chain_expr(chain),
+            Expr::Update(update) => self.visit_update_expr(update),
+            Expr::Conditional(conditional) => self.visit_conditional_expr(conditional),
         }
     }
 
@@ -241,97 +787,7 @@ impl ExprVisitor<Result<Literal, LoxError>> for Interpreter {
         let left_result = binary_expr.left.accept(self)?;
         let right_result = binary_expr.right.accept(self)?;
 
-        match binary_expr.operator.ty {
-            TokenType::Comma => Ok(right_result.clone()),
-            TokenType::Minus => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Number(left - right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            TokenType::Plus => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Number(left + right))
-                }
-                (Literal::String(left), Literal::String(right)) => {
-                    Ok(Literal::String(format!("{}{}", left, right)))
-                }
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be two numbers or two strings.".into(),
-                }),
-            },
-            TokenType::Slash => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Number(left / right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            TokenType::Star => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Number(left * right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            TokenType::BangEqual => self.are_equal(left_result, right_result, true),
-            TokenType::EqualEqual => self.are_equal(left_result, right_result, false),
-            TokenType::Greater => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Boolean(left > right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            TokenType::GreaterEqual => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Boolean(left >= right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            TokenType::Less => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Boolean(left < right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            TokenType::LessEqual => match (left_result, right_result) {
-                (Literal::Number(left), Literal::Number(right)) => {
-                    Ok(Literal::Boolean(left <= right))
-                }
-
-                (_, _) => Err(LoxError::Runtime {
-                    token: binary_expr.operator.clone(),
-                    message: "Operands must be numbers.".into(),
-                }),
-            },
-            _ => Err(LoxError::Runtime {
-                token: binary_expr.operator.clone(),
-                message: "Invalid binary expression found".into(),
-            }),
-        }
+        self.binary(&binary_expr.operator, left_result, right_result)
     }
 
     fn visit_grouping_expr(&mut self, grouping_expr: &Grouping) -> Result<Literal, LoxError> {
@@ -346,14 +802,12 @@ impl ExprVisitor<Result<Literal, LoxError>> for Interpreter {
         let right_result = unary_expr.right.accept(self)?;
 
         match unary_expr.operator.ty {
-            TokenType::Minus => match right_result {
-                Literal::Number(number) => Ok(Literal::Number(number * -1.0)),
-                _ => Err(LoxError::Runtime {
-                    token: unary_expr.operator.clone(),
-                    message: "Operand must be a number.".into(),
-                }),
-            },
+            TokenType::Minus => numeric::negate(&unary_expr.operator, &right_result),
             TokenType::Bang => self.is_truthy(right_result, true),
+            TokenType::Tilde => {
+                let integer = numeric::integer_operand(&unary_expr.operator, &right_result)?;
+                Ok(Literal::Integer(!integer))
+            }
             _ => Err(LoxError::Runtime {
                 token: unary_expr.operator.clone(),
                 message: "Invalid unary expression found".into(),
@@ -370,6 +824,15 @@ impl ExprVisitor<Result<Literal, LoxError>> for Interpreter {
         self.get_environment().assign(&assign_expr.token, &value)
     }
 
+    fn visit_destructure_assign_expr(
+        &mut self,
+        destructure_expr: &DestructureAssign,
+    ) -> Result<Literal, LoxError> {
+        let value = destructure_expr.value.accept(self)?;
+        self.destructure(&destructure_expr.pattern, value.clone(), None)?;
+        Ok(value)
+    }
+
     fn visit_logical_expr(&mut self, expr: &Logical) -> Result<Literal, LoxError> {
         let left = expr.left.accept(self)?;
 
@@ -381,20 +844,145 @@ impl ExprVisitor<Result<Literal, LoxError>> for Interpreter {
                 (TokenType::And, false) => {
                     return Ok(left);
                 }
+                (TokenType::QuestionQuestion, _) if left != Literal::Nil => {
+                    return Ok(left);
+                }
                 _ => {}
             }
         }
 
-        return expr.right.accept(self);
+        expr.right.accept(self)
     }
 
     fn visit_call_expr(&mut self, call_expr: &Call) -> Result<Literal, LoxError> {
         let callee = call_expr.callee.accept(self)?;
-        let mut arguments: Vec<Literal> = vec![];
-        for argument in &call_expr.arguments {
-            arguments.push(argument.accept(self)?);
-        }
+        let arguments = self.arguments(call_expr)?;
 
         callee.call(self, &call_expr.paren, arguments)
     }
+
+    fn visit_list_expr(&mut self, list_expr: &List) -> Result<Literal, LoxError> {
+        let mut elements = vec![];
+        for element in &list_expr.elements {
+            elements.push(element.accept(self)?);
+        }
+
+        let list = LoxList::new(&self.memory, elements);
+        Ok(Literal::List(Rc::new(RefCell::new(list))))
+    }
+
+    fn visit_map_expr(&mut self, map_expr: &Map) -> Result<Literal, LoxError> {
+        let mut map = LoxMap::new(&self.memory);
+        for (key, value) in &map_expr.entries {
+            let key = key.accept(self)?;
+            let value = value.accept(self)?;
+            map.insert(map_key(&map_expr.brace, &key)?, value);
+        }
+
+        Ok(Literal::Map(Rc::new(RefCell::new(map))))
+    }
+
+    fn visit_index_expr(&mut self, index_expr: &Index) -> Result<Literal, LoxError> {
+        let object = index_expr.object.accept(self)?;
+        let index = index_expr.index.accept(self)?;
+
+        get_index(&index_expr.bracket, &object, &index)
+    }
+
+    fn visit_index_set_expr(&mut self, index_set_expr: &IndexSet) -> Result<Literal, LoxError> {
+        let object = index_set_expr.object.accept(self)?;
+        let index = index_set_expr.index.accept(self)?;
+        let value = index_set_expr.value.accept(self)?;
+
+        set_index(&index_set_expr.bracket, &object, &index, value)
+    }
+
+    fn visit_lambda_expr(&mut self, lambda_expr: &Lambda) -> Result<Literal, LoxError> {
+        Ok(Literal::Function(FunctionLiteral {
+            node: Rc::new(lambda_expr.function.clone()),
+            closure: Rc::clone(&self.environment),
+            script: Rc::clone(&self.script),
+        }))
+    }
+
+    fn visit_get_expr(&mut self, get_expr: &Get) -> Result<Literal, LoxError> {
+        match get_expr.object.accept(self)? {
+            Literal::Nil if get_expr.optional => Err(LoxError::ShortCircuit),
+            Literal::Module(module) => {
+                module
+                    .globals
+                    .borrow()
+                    .member(&get_expr.name)
+                    .ok_or_else(|| LoxError::Runtime {
+                        token: get_expr.name.clone(),
+                        message: format!("Undefined property '{}'.", get_expr.name.lexeme),
+                    })
+            }
+            _ => Err(LoxError::Runtime {
+                token: get_expr.name.clone(),
+                message: "Only modules have properties.".into(),
+            }),
+        }
+    }
+
+    fn visit_interpolation_expr(
+        &mut self,
+        interpolation_expr: &Interpolation,
+    ) -> Result<Literal, LoxError> {
+        let mut string = String::new();
+        for part in &interpolation_expr.parts {
+            string.push_str(&part.accept(self)?.to_string());
+        }
+        Ok(Literal::String(string.into()))
+    }
+
+    fn visit_optional_chain_expr(
+        &mut self,
+        chain_expr: &OptionalChain,
+    ) -> Result<Literal, LoxError> {
+        match chain_expr.expression.accept(self) {
+            Err(LoxError::ShortCircuit) => Ok(Literal::Nil),
+            result => result,
+        }
+    }
+
+    fn visit_update_expr(&mut self, update_expr: &Update) -> Result<Literal, LoxError> {
+        // the target's object and index are evaluated once, for both the read and the write
+        let (old, new) = match update_expr.target.as_ref() {
+            Expr::Variable(variable) => {
+                let old = self.get_environment().get(&variable.token)?;
+                let new = self.updated(update_expr, &old)?;
+                self.get_environment().assign(&variable.token, &new)?;
+                (old, new)
+            }
+            Expr::Index(index) => {
+                let object = index.object.accept(self)?;
+                let key = index.index.accept(self)?;
+                let old = get_index(&index.bracket, &object, &key)?;
+                let new = self.updated(update_expr, &old)?;
+                set_index(&index.bracket, &object, &key, new.clone())?;
+                (old, new)
+            }
+            _ => {
+                return Err(LoxError::Runtime {
+                    token: update_expr.operator.clone(),
+                    message: "Invalid assignment target.".into(),
+                })
+            }
+        };
+
+        Ok(if update_expr.postfix { old } else { new })
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        conditional_expr: &Conditional,
+    ) -> Result<Literal, LoxError> {
+        let condition = conditional_expr.condition.accept(self)?;
+
+        match self.is_truthy(condition, false)? {
+            Literal::Boolean(true) => conditional_expr.then_branch.accept(self),
+            _ => conditional_expr.else_branch.accept(self),
+        }
+    }
 }
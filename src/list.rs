@@ -0,0 +1,81 @@
+use std::{ops::Deref, rc::Rc};
+
+use crate::{interpreter::memory::Memory, literal::Literal};
+
+// The elements of a list. Their size is charged to the interpreter's memory
+// while they are in the list, and released when they are removed or the
+// list itself is dropped.
+#[derive(Debug)]
+pub struct LoxList {
+    items: Vec<Literal>,
+    memory: Rc<Memory>,
+    // bytes currently charged to `memory` by this list
+    size: usize,
+}
+
+impl LoxList {
+    pub fn new(memory: &Rc<Memory>, items: Vec<Literal>) -> Self {
+        let size = items.iter().map(Memory::value_size).sum();
+        memory.allocate(size);
+
+        Self {
+            items,
+            memory: Rc::clone(memory),
+            size,
+        }
+    }
+
+    pub fn push(&mut self, value: Literal) {
+        self.charge(&value);
+        self.items.push(value);
+    }
+
+    pub fn pop(&mut self) -> Option<Literal> {
+        let value = self.items.pop()?;
+        self.refund(&value);
+        Some(value)
+    }
+
+    pub fn insert(&mut self, index: usize, value: Literal) {
+        self.charge(&value);
+        self.items.insert(index, value);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Literal {
+        let value = self.items.remove(index);
+        self.refund(&value);
+        value
+    }
+
+    pub fn set(&mut self, index: usize, value: Literal) {
+        self.charge(&value);
+        let previous = std::mem::replace(&mut self.items[index], value);
+        self.refund(&previous);
+    }
+
+    fn charge(&mut self, value: &Literal) {
+        let bytes = Memory::value_size(value);
+        self.size += bytes;
+        self.memory.allocate(bytes);
+    }
+
+    fn refund(&mut self, value: &Literal) {
+        let bytes = Memory::value_size(value);
+        self.size -= bytes;
+        self.memory.release(bytes);
+    }
+}
+
+impl Deref for LoxList {
+    type Target = [Literal];
+
+    fn deref(&self) -> &[Literal] {
+        &self.items
+    }
+}
+
+impl Drop for LoxList {
+    fn drop(&mut self) {
+        self.memory.release(self.size);
+    }
+}
@@ -1,59 +1,214 @@
 use std::{
     cell::RefCell,
+    cmp::Ordering,
+    collections::HashSet,
     fmt::{self, Display, Formatter},
+    path::PathBuf,
     rc::Rc,
 };
 
-use crate::{ast::nodes::FunctionStmt, interpreter::environment::Environment};
+use crate::{
+    ast::nodes::FunctionStmt,
+    bigint::BigInt,
+    interpreter::{environment::Environment, numeric},
+    list::LoxList,
+    map::LoxMap,
+};
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum NativeFunction {
     Clock,
+    Len,
+    Push,
+    Pop,
+    Insert,
+    Remove,
+    Slice,
+    Keys,
+    Values,
+    Has,
+    Delete,
+    Int,
+    Float,
+}
+
+impl NativeFunction {
+    pub const ALL: [NativeFunction; 13] = [
+        NativeFunction::Clock,
+        NativeFunction::Len,
+        NativeFunction::Push,
+        NativeFunction::Pop,
+        NativeFunction::Insert,
+        NativeFunction::Remove,
+        NativeFunction::Slice,
+        NativeFunction::Keys,
+        NativeFunction::Values,
+        NativeFunction::Has,
+        NativeFunction::Delete,
+        NativeFunction::Int,
+        NativeFunction::Float,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NativeFunction::Clock => "clock",
+            NativeFunction::Len => "len",
+            NativeFunction::Push => "push",
+            NativeFunction::Pop => "pop",
+            NativeFunction::Insert => "insert",
+            NativeFunction::Remove => "remove",
+            NativeFunction::Slice => "slice",
+            NativeFunction::Keys => "keys",
+            NativeFunction::Values => "values",
+            NativeFunction::Has => "has",
+            NativeFunction::Delete => "delete",
+            NativeFunction::Int => "int",
+            NativeFunction::Float => "float",
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+pub type ListLiteral = Rc<RefCell<LoxList>>;
+pub type MapLiteral = Rc<RefCell<LoxMap>>;
+
+#[derive(Debug, Clone)]
 pub struct FunctionLiteral {
-    pub node: FunctionStmt,
+    pub node: Rc<FunctionStmt>,
     pub closure: Rc<RefCell<Environment>>,
+    // the file the function was defined in
+    pub script: Rc<PathBuf>,
+}
+
+// A loaded module: the global environment its file was executed in.
+#[derive(Debug)]
+pub struct Module {
+    pub path: Rc<PathBuf>,
+    pub globals: Rc<RefCell<Environment>>,
 }
 
-#[derive(Debug, PartialEq)]
+impl PartialEq for FunctionLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        // closures can contain the function itself, so compare them by identity
+        Rc::ptr_eq(&self.closure, &other.closure) && self.node == other.node
+    }
+}
+
+#[derive(Debug)]
 pub enum Literal {
     Nil,
-    String(String),
+    String(Rc<str>),
+    Integer(i64),
+    // integers too wide for an i64, and any arithmetic involving them
+    BigInt(Rc<BigInt>),
     Number(f64),
     Boolean(bool),
     Function(FunctionLiteral),
     NativeFunction(NativeFunction),
+    // lists are shared by reference, so mutation is visible through every copy
+    List(ListLiteral),
+    Map(MapLiteral),
+    Module(Rc<Module>),
 }
 
 impl Clone for Literal {
     fn clone(&self) -> Self {
         match self {
-            Self::String(string) => Self::String(string.clone()),
-            Self::Number(number) => Self::Number(number.clone()),
-            Self::Boolean(boolean) => Self::Boolean(boolean.clone()),
+            Self::String(string) => Self::String(Rc::clone(string)),
+            Self::Integer(integer) => Self::Integer(*integer),
+            Self::BigInt(big) => Self::BigInt(Rc::clone(big)),
+            Self::Number(number) => Self::Number(*number),
+            Self::Boolean(boolean) => Self::Boolean(*boolean),
             Self::Function(function) => Self::Function(function.clone()),
-            Self::NativeFunction(function) => Self::NativeFunction(function.clone()),
+            Self::NativeFunction(function) => Self::NativeFunction(*function),
+            Self::List(list) => Self::List(Rc::clone(list)),
+            Self::Map(map) => Self::Map(Rc::clone(map)),
+            Self::Module(module) => Self::Module(Rc::clone(module)),
             Self::Nil => Self::Nil,
         }
     }
 }
 
+impl PartialEq for Literal {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // interned strings share one allocation, so the pointer check settles most cases
+            (Literal::String(left), Literal::String(right)) => {
+                Rc::ptr_eq(left, right) || left == right
+            }
+            (Literal::Integer(left), Literal::Integer(right)) => left == right,
+            (Literal::Number(left), Literal::Number(right)) => left == right,
+            (Literal::BigInt(left), Literal::BigInt(right)) => left == right,
+            (
+                Literal::Integer(_) | Literal::BigInt(_) | Literal::Number(_),
+                Literal::Integer(_) | Literal::BigInt(_) | Literal::Number(_),
+            ) => numeric::ordering(self, other) == Some(Ordering::Equal),
+            (Literal::Boolean(left), Literal::Boolean(right)) => left == right,
+            (Literal::List(left), Literal::List(right)) => Rc::ptr_eq(left, right),
+            (Literal::Map(left), Literal::Map(right)) => Rc::ptr_eq(left, right),
+            (Literal::Module(left), Literal::Module(right)) => Rc::ptr_eq(left, right),
+            (Literal::Function(left), Literal::Function(right)) => left == right,
+            (Literal::NativeFunction(left), Literal::NativeFunction(right)) => left == right,
+            (Literal::Nil, Literal::Nil) => true,
+            _ => false,
+        }
+    }
+}
+
 impl Literal {
+    // The exact i64 this value holds: an integer, or a bigint or float with no
+    // fractional part that fits in one.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Literal::Integer(integer) => Some(*integer),
+            Literal::BigInt(big) => big.to_i64(),
+            // i64::MAX isn't exactly representable, so compare against 2^63 instead
+            Literal::Number(number)
+                if number.fract() == 0.0
+                    && *number >= i64::MIN as f64
+                    && *number < -(i64::MIN as f64) =>
+            {
+                Some(*number as i64)
+            }
+            _ => None,
+        }
+    }
+
+    // The exact integer this value holds, however wide.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Literal::Integer(integer) => Some(BigInt::from(*integer)),
+            Literal::BigInt(big) => Some(BigInt::clone(big)),
+            Literal::Number(number) => BigInt::from_f64(*number),
+            _ => None,
+        }
+    }
+
     pub fn token_print(&self) -> String {
         match self {
             Literal::String(string) => format!("{}", string),
+            Literal::Integer(integer) => format!("{}", integer),
+            Literal::BigInt(big) => format!("{}n", big),
             Literal::Number(number) => format!("{:?}", number),
-            _ => format!("null"),
+            _ => "null".to_string(),
         }
     }
 }
 
 impl Display for Literal {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, &mut HashSet::new())
+    }
+}
+
+impl Literal {
+    // Writes the value, printing a list or map that contains itself, directly
+    // or through other values, as `[...]` or `{...}` where it recurs.
+    // `printing` holds the lists and maps currently being written.
+    fn write(&self, f: &mut Formatter<'_>, printing: &mut HashSet<*const ()>) -> fmt::Result {
         match self {
             Literal::String(string) => write!(f, "{}", string),
+            Literal::Integer(integer) => write!(f, "{}", integer),
+            Literal::BigInt(big) => write!(f, "{}", big),
             Literal::Number(number) => write!(f, "{}", number),
             Literal::Boolean(boolean) => write!(f, "{}", boolean),
             Literal::NativeFunction(_function) => write!(f, "<fn native>"),
@@ -61,6 +216,52 @@ impl Display for Literal {
             Literal::Function(function) => {
                 write!(f, "<fn {}>", function.node.name.lexeme.clone())
             }
+            Literal::List(list) => {
+                let pointer = Rc::as_ptr(list) as *const ();
+                if !printing.insert(pointer) {
+                    return write!(f, "[...]");
+                }
+                write!(f, "[")?;
+                for (index, element) in list.borrow().iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    element.write_element(f, printing)?;
+                }
+                printing.remove(&pointer);
+                write!(f, "]")
+            }
+            Literal::Map(map) => {
+                let pointer = Rc::as_ptr(map) as *const ();
+                if !printing.insert(pointer) {
+                    return write!(f, "{{...}}");
+                }
+                write!(f, "{{")?;
+                for (index, (key, value)) in map.borrow().iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    key.to_literal().write_element(f, printing)?;
+                    write!(f, ": ")?;
+                    value.write_element(f, printing)?;
+                }
+                printing.remove(&pointer);
+                write!(f, "}}")
+            }
+            Literal::Module(module) => write!(f, "<module {}>", module.path.display()),
+        }
+    }
+
+    // Writes a value held inside a list or map, quoting strings so that
+    // `{"1": 1}` and `{1: 1}` print differently.
+    fn write_element(
+        &self,
+        f: &mut Formatter<'_>,
+        printing: &mut HashSet<*const ()>,
+    ) -> fmt::Result {
+        match self {
+            Literal::String(string) => write!(f, "{:?}", string),
+            _ => self.write(f, printing),
         }
     }
 }
@@ -1,37 +1,65 @@
 mod ast;
+mod bigint;
+mod cli;
 mod error;
+mod interner;
 mod interpreter;
+mod list;
 mod literal;
+mod map;
+mod optimizer;
 mod parser;
 mod scanner;
 mod token;
 
 use std::env;
 use std::fs;
-use std::io::{self, Write};
+use std::panic;
+use std::path::Path;
 use std::process::exit;
+use std::thread;
 
-use interpreter::Interpreter;
+use ast::printer::AstPrinter;
+use cli::{Emit, Options, STACK_BYTES_PER_CALL};
+use interpreter::{resolver::Resolver, Interpreter};
+use optimizer::Optimizer;
 use parser::Parser;
 use scanner::Scanner;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let filename = if args.len() > 1 {
-        &args[1]
-    } else {
-        &String::from("test.lox")
-    };
+    let options = Options::parse(env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        eprintln!("{}", cli::USAGE);
+        exit(64)
+    });
+
+    // the depth is at most what cli::MAX_STACK_BYTES holds
+    let stack_size = options.max_call_depth * STACK_BYTES_PER_CALL;
+    let error_code = thread::Builder::new()
+        .stack_size(stack_size)
+        .spawn(move || run(options, stack_size))
+        .expect("failed to spawn interpreter thread")
+        .join()
+        // a panic is a bug in the interpreter, not an error in the script
+        .unwrap_or_else(|payload| panic::resume_unwind(payload));
+
+    if let Some(error_code) = error_code {
+        exit(error_code)
+    }
+}
+
+fn run(options: Options, stack_size: usize) -> Option<i32> {
+    let filename = &options.filename;
 
     let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-        writeln!(io::stderr(), "failed to read file {}", filename).unwrap();
+        eprintln!("failed to read file {}", filename);
         String::new()
     });
 
     let mut scanner = Scanner::new(&file_contents);
 
     let output = scanner.run();
-    let mut has_errors = output.errors.len() > 0;
+    let mut has_errors = !output.errors.is_empty();
     let mut error_code = 65;
 
     for error in output.errors {
@@ -40,24 +68,53 @@ fn main() {
 
     let mut parser = Parser::new(&output.tokens);
     let mut interpreter = Interpreter::new();
+    interpreter.max_call_depth = options.max_call_depth;
+    // what is left once the scanner and parser have run stays in reserve
+    interpreter.max_stack_bytes = stack_size - stack_size / 4;
+    interpreter.budget.max_steps = options.max_steps;
+    interpreter.budget.timeout = options.timeout;
+    interpreter.memory.limit.set(options.max_memory);
+    interpreter.modules.search_path = options.module_path.clone();
+    interpreter.modules.opt_level = options.opt_level;
+    interpreter.enter_script(Path::new(filename));
 
     match parser.parse() {
-        Ok(statements) => match interpreter.interpret(&statements) {
-            Ok(_result) => {}
-            Err(error) => {
-                // runtime error
+        Ok(mut statements) => {
+            if let Err(error) = Resolver::new().resolve_stmts(&statements) {
+                // compiler time error
                 has_errors = true;
                 error.log();
-                error_code = 70;
+            } else {
+                if options.opt_level > 0 {
+                    statements = Optimizer::new().optimize(&statements);
+                }
+
+                if options.emit == Emit::OptimizedAst {
+                    println!("{}", AstPrinter.print(&statements));
+                } else if let Err(error) = interpreter.interpret(&statements) {
+                    // runtime error
+                    has_errors = true;
+                    error.log();
+                    error_code = 70;
+                }
             }
-        },
-        Err(_errors) => {
+        }
+        Err(errors) => {
             // compiler time error
             has_errors = true;
+            for error in errors {
+                error.log();
+            }
         }
     };
 
-    if has_errors {
-        exit(error_code)
+    if options.memory_stats {
+        eprintln!(
+            "memory: {} bytes in use, {} bytes peak",
+            interpreter.memory.current(),
+            interpreter.memory.peak()
+        );
     }
+
+    has_errors.then_some(error_code)
 }
@@ -0,0 +1,133 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{bigint::BigInt, interpreter::memory::Memory, literal::Literal};
+
+// The subset of values that can be used as map keys. Numbers holding an exact
+// integer are stored as the narrowest integer that fits, so `m[1]`, `m[1n]`
+// and `m[1.0]` are the same entry. Other floats are stored by their bit
+// pattern, with -0.0 folded into 0.0 so equal numbers hash equally.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum MapKey {
+    Nil,
+    Boolean(bool),
+    Integer(i64),
+    BigInt(Rc<BigInt>),
+    Number(u64),
+    String(Rc<str>),
+}
+
+impl MapKey {
+    pub fn from_literal(literal: &Literal) -> Option<Self> {
+        match literal {
+            Literal::Nil => Some(MapKey::Nil),
+            Literal::Boolean(boolean) => Some(MapKey::Boolean(*boolean)),
+            Literal::Integer(integer) => Some(MapKey::Integer(*integer)),
+            Literal::BigInt(big) => Some(match big.to_i64() {
+                Some(integer) => MapKey::Integer(integer),
+                None => MapKey::BigInt(Rc::clone(big)),
+            }),
+            Literal::Number(number) => Some(match literal.as_integer() {
+                Some(integer) => MapKey::Integer(integer),
+                None => match BigInt::from_f64(*number) {
+                    Some(big) => MapKey::BigInt(Rc::new(big)),
+                    None => MapKey::Number((number + 0.0).to_bits()),
+                },
+            }),
+            Literal::String(string) => Some(MapKey::String(Rc::clone(string))),
+            _ => None,
+        }
+    }
+
+    pub fn to_literal(&self) -> Literal {
+        match self {
+            MapKey::Nil => Literal::Nil,
+            MapKey::Boolean(boolean) => Literal::Boolean(*boolean),
+            MapKey::Integer(integer) => Literal::Integer(*integer),
+            MapKey::BigInt(big) => Literal::BigInt(Rc::clone(big)),
+            MapKey::Number(bits) => Literal::Number(f64::from_bits(*bits)),
+            MapKey::String(string) => Literal::String(Rc::clone(string)),
+        }
+    }
+}
+
+// A hash map that remembers insertion order, which is the order keys(),
+// values() and printing observe. Overwriting a key keeps its position. Keys
+// and values are charged to the interpreter's memory while they are stored.
+#[derive(Debug)]
+pub struct LoxMap {
+    entries: Vec<(MapKey, Literal)>,
+    indices: HashMap<MapKey, usize>,
+    memory: Rc<Memory>,
+    // bytes currently charged to `memory` by this map
+    size: usize,
+}
+
+impl LoxMap {
+    pub fn new(memory: &Rc<Memory>) -> Self {
+        Self {
+            entries: vec![],
+            indices: HashMap::new(),
+            memory: Rc::clone(memory),
+            size: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&Literal> {
+        self.indices.get(key).map(|index| &self.entries[*index].1)
+    }
+
+    pub fn contains_key(&self, key: &MapKey) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    pub fn insert(&mut self, key: MapKey, value: Literal) {
+        self.charge(Memory::value_size(&value));
+        match self.indices.get(&key) {
+            Some(index) => {
+                let previous = std::mem::replace(&mut self.entries[*index].1, value);
+                self.refund(Memory::value_size(&previous));
+            }
+            None => {
+                self.charge(Memory::value_size(&key.to_literal()));
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<Literal> {
+        let index = self.indices.remove(key)?;
+        let (key, value) = self.entries.remove(index);
+        self.refund(Memory::value_size(&key.to_literal()) + Memory::value_size(&value));
+        for (key, _) in &self.entries[index..] {
+            if let Some(position) = self.indices.get_mut(key) {
+                *position -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(MapKey, Literal)> {
+        self.entries.iter()
+    }
+
+    fn charge(&mut self, bytes: usize) {
+        self.size += bytes;
+        self.memory.allocate(bytes);
+    }
+
+    fn refund(&mut self, bytes: usize) {
+        self.size -= bytes;
+        self.memory.release(bytes);
+    }
+}
+
+impl Drop for LoxMap {
+    fn drop(&mut self) {
+        self.memory.release(self.size);
+    }
+}
@@ -0,0 +1,596 @@
+use crate::{
+    ast::{
+        nodes::*,
+        traits::{ExprVisitor, StmtVisitor, VisitExpr, VisitStmt},
+    },
+    interpreter::Interpreter,
+    literal::Literal,
+    token::TokenType,
+};
+
+// Folding gives up on constants that take more work or memory than this, such
+// as `2 ** 30000000`, and leaves them for the runtime and its own limits.
+const FOLD_MAX_STEPS: u64 = 10_000;
+const FOLD_MAX_BYTES: usize = 64 * 1024;
+
+// Rewrites the tree before it is interpreted: folds operations on constant
+// operands and removes statements that can never run. Statement visits return
+// `None` when the statement is dropped entirely.
+pub struct Optimizer {
+    // constant subtrees have no side effects, so they are folded by simply
+    // evaluating them; anything that errors is left for the runtime to report
+    evaluator: Interpreter,
+}
+
+impl Optimizer {
+    pub fn new() -> Self {
+        let mut evaluator = Interpreter::new();
+        evaluator.budget.max_steps = Some(FOLD_MAX_STEPS);
+        evaluator.memory.limit.set(Some(FOLD_MAX_BYTES));
+        Self { evaluator }
+    }
+
+    pub fn optimize(&mut self, statements: &[Stmt]) -> Vec<Stmt> {
+        statements
+            .iter()
+            .filter_map(|statement| statement.accept(self))
+            .collect()
+    }
+
+    fn optimize_function(&mut self, function: &FunctionStmt) -> FunctionStmt {
+        FunctionStmt {
+            name: function.name.clone(),
+            params: function
+                .params
+                .iter()
+                .map(|param| Param {
+                    name: param.name.clone(),
+                    default: param.default.as_ref().map(|default| default.accept(self)),
+                })
+                .collect(),
+            rest: function.rest.clone(),
+            body: self.optimize_block(&function.body),
+        }
+    }
+
+    fn optimize_pattern(&mut self, pattern: &Pattern) -> Pattern {
+        match pattern {
+            Pattern::Name(name) => Pattern::Name(name.clone()),
+            Pattern::Index(index) => Pattern::Index(Index {
+                object: Box::new(index.object.accept(self)),
+                bracket: index.bracket.clone(),
+                index: Box::new(index.index.accept(self)),
+            }),
+            Pattern::List { bracket, elements } => Pattern::List {
+                bracket: bracket.clone(),
+                elements: elements
+                    .iter()
+                    .map(|element| self.optimize_pattern_element(element))
+                    .collect(),
+            },
+            Pattern::Map { brace, entries } => Pattern::Map {
+                brace: brace.clone(),
+                entries: entries
+                    .iter()
+                    .map(|(key, element)| (key.clone(), self.optimize_pattern_element(element)))
+                    .collect(),
+            },
+        }
+    }
+
+    fn optimize_pattern_element(&mut self, element: &PatternElement) -> PatternElement {
+        PatternElement {
+            target: self.optimize_pattern(&element.target),
+            default: element.default.as_ref().map(|default| default.accept(self)),
+        }
+    }
+
+    fn optimize_block(&mut self, statements: &[Stmt]) -> Vec<Stmt> {
+        let mut optimized = vec![];
+        let mut unreachable = false;
+        for statement in statements {
+            if unreachable {
+                // a `let` binding is reserved for its whole block, even where its
+                // declaration can't run, so it still shadows enclosing names
+                let hoisted = match statement {
+                    Stmt::Variable(variable_stmt) => variable_stmt.kind == DeclarationKind::Let,
+                    Stmt::Destructure(destructure_stmt) => {
+                        destructure_stmt.kind == DeclarationKind::Let
+                    }
+                    _ => false,
+                };
+                if hoisted {
+                    optimized.push(statement.clone());
+                }
+            } else if let Some(statement) = statement.accept(self) {
+                // nothing after a return, break, continue or throw in the same block can run
+                unreachable = matches!(
+                    statement,
+                    Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) | Stmt::Throw(_)
+                );
+                optimized.push(statement);
+            }
+        }
+        optimized
+    }
+
+    fn optimize_branch(&mut self, statement: &Stmt) -> Box<Stmt> {
+        Box::new(
+            statement
+                .accept(self)
+                .unwrap_or(Stmt::Block(BlockStmt { statements: vec![] })),
+        )
+    }
+
+    fn fold(&mut self, expr: Expr) -> Expr {
+        self.evaluator.budget.start();
+        match self.evaluator.visit_expr(&expr) {
+            Ok(literal) => Expr::Literal(Lit { literal }),
+            Err(_) => expr,
+        }
+    }
+
+    fn constant_truthiness(expr: &Expr) -> Option<bool> {
+        match expr {
+            Expr::Literal(Lit { literal }) => match literal {
+                Literal::Nil => Some(false),
+                Literal::Boolean(boolean) => Some(*boolean),
+                Literal::Integer(integer) => Some(*integer != 0),
+                Literal::BigInt(big) => Some(!big.is_zero()),
+                Literal::Number(number) => Some(*number != 0.0),
+                Literal::String(_) => Some(true),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn is_boolean(expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(Lit {
+                literal: Literal::Boolean(_),
+            }) => true,
+            Expr::Grouping(grouping) => Self::is_boolean(&grouping.expression),
+            Expr::Unary(unary) => unary.operator.ty == TokenType::Bang,
+            Expr::Binary(binary) => matches!(
+                binary.operator.ty,
+                TokenType::EqualEqual
+                    | TokenType::BangEqual
+                    | TokenType::Greater
+                    | TokenType::GreaterEqual
+                    | TokenType::Less
+                    | TokenType::LessEqual
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl StmtVisitor<Option<Stmt>> for Optimizer {
+    fn visit_statement(&mut self, stmt: &Stmt) -> Option<Stmt> {
+        match stmt {
+            Stmt::Block(block_stmt) => self.visit_block(block_stmt),
+            Stmt::Expression(expr_stmt) => self.visit_expression(expr_stmt),
+            Stmt::Print(print_stmt) => self.visit_print(print_stmt),
+            Stmt::Variable(variable_stmt) => self.visit_variable_declaration(variable_stmt),
+            Stmt::Destructure(destructure_stmt) => self.visit_destructure(destructure_stmt),
+            Stmt::If(if_stmt) => self.visit_if(if_stmt),
+            Stmt::While(while_stmt) => self.visit_while(while_stmt),
+            Stmt::For(for_stmt) => self.visit_for(for_stmt),
+            Stmt::ForIn(for_in_stmt) => self.visit_for_in(for_in_stmt),
+            Stmt::Function(function_stmt) => self.visit_function(function_stmt),
+            Stmt::Return(return_stmt) => self.visit_return(return_stmt),
+            Stmt::Break(break_stmt) => self.visit_break(break_stmt),
+            Stmt::Continue(continue_stmt) => self.visit_continue(continue_stmt),
+            Stmt::Throw(throw_stmt) => self.visit_throw(throw_stmt),
+            Stmt::Try(try_stmt) => self.visit_try(try_stmt),
+            Stmt::Import(import_stmt) => self.visit_import(import_stmt),
+        }
+    }
+
+    fn visit_expression(&mut self, expr_stmt: &ExpressionStmt) -> Option<Stmt> {
+        Some(Stmt::Expression(ExpressionStmt {
+            expression: expr_stmt.expression.accept(self),
+        }))
+    }
+
+    fn visit_print(&mut self, print_stmt: &PrintStmt) -> Option<Stmt> {
+        Some(Stmt::Print(PrintStmt {
+            expression: print_stmt.expression.accept(self),
+        }))
+    }
+
+    fn visit_variable_declaration(
+        &mut self,
+        variable_stmt: &VariableDeclarationStmt,
+    ) -> Option<Stmt> {
+        Some(Stmt::Variable(VariableDeclarationStmt {
+            token: variable_stmt.token.clone(),
+            initializer: variable_stmt.initializer.accept(self),
+            kind: variable_stmt.kind,
+        }))
+    }
+
+    fn visit_destructure(&mut self, destructure_stmt: &DestructureStmt) -> Option<Stmt> {
+        Some(Stmt::Destructure(DestructureStmt {
+            pattern: self.optimize_pattern(&destructure_stmt.pattern),
+            initializer: destructure_stmt.initializer.accept(self),
+            kind: destructure_stmt.kind,
+        }))
+    }
+
+    fn visit_block(&mut self, block_stmt: &BlockStmt) -> Option<Stmt> {
+        Some(Stmt::Block(BlockStmt {
+            statements: self.optimize_block(&block_stmt.statements),
+        }))
+    }
+
+    fn visit_if(&mut self, if_stmt: &IfStmt) -> Option<Stmt> {
+        let condition = if_stmt.condition.accept(self);
+
+        match Self::constant_truthiness(&condition) {
+            Some(true) => if_stmt.then_branch.accept(self),
+            Some(false) => match &if_stmt.else_branch {
+                Some(else_branch) => else_branch.accept(self),
+                None => None,
+            },
+            None => Some(Stmt::If(IfStmt {
+                condition,
+                then_branch: self.optimize_branch(&if_stmt.then_branch),
+                else_branch: if_stmt
+                    .else_branch
+                    .as_ref()
+                    .map(|else_branch| self.optimize_branch(else_branch)),
+            })),
+        }
+    }
+
+    fn visit_while(&mut self, while_stmt: &WhileStmt) -> Option<Stmt> {
+        let condition = while_stmt.condition.accept(self);
+
+        if Self::constant_truthiness(&condition) == Some(false) {
+            return None;
+        }
+
+        Some(Stmt::While(WhileStmt {
+            condition,
+            body: self.optimize_branch(&while_stmt.body),
+        }))
+    }
+
+    fn visit_for(&mut self, for_stmt: &ForStmt) -> Option<Stmt> {
+        let initializer = for_stmt
+            .initializer
+            .as_ref()
+            .and_then(|initializer| initializer.accept(self));
+        let condition = for_stmt
+            .condition
+            .as_ref()
+            .map(|condition| condition.accept(self));
+
+        // the initializer still runs once, in the enclosing scope unless it
+        // declares a binding of the loop's own
+        if condition.as_ref().and_then(Self::constant_truthiness) == Some(false) {
+            return initializer.map(|initializer| match initializer {
+                Stmt::Variable(ref variable_stmt) if variable_stmt.kind != DeclarationKind::Var => {
+                    Stmt::Block(BlockStmt {
+                        statements: vec![initializer],
+                    })
+                }
+                initializer => initializer,
+            });
+        }
+
+        Some(Stmt::For(ForStmt {
+            initializer: initializer.map(Box::new),
+            condition,
+            increment: for_stmt
+                .increment
+                .as_ref()
+                .map(|increment| increment.accept(self)),
+            body: self.optimize_branch(&for_stmt.body),
+        }))
+    }
+
+    fn visit_for_in(&mut self, for_in_stmt: &ForInStmt) -> Option<Stmt> {
+        let iterable = match &for_in_stmt.iterable {
+            Iterable::Value(value) => Iterable::Value(value.accept(self)),
+            Iterable::Range {
+                start,
+                operator,
+                end,
+            } => Iterable::Range {
+                start: start.accept(self),
+                operator: operator.clone(),
+                end: end.accept(self),
+            },
+        };
+
+        Some(Stmt::ForIn(ForInStmt {
+            name: for_in_stmt.name.clone(),
+            kind: for_in_stmt.kind,
+            keyword: for_in_stmt.keyword.clone(),
+            iterable,
+            body: self.optimize_branch(&for_in_stmt.body),
+        }))
+    }
+
+    fn visit_function(&mut self, function_stmt: &FunctionStmt) -> Option<Stmt> {
+        Some(Stmt::Function(self.optimize_function(function_stmt)))
+    }
+
+    fn visit_return(&mut self, return_stmt: &ReturnStmt) -> Option<Stmt> {
+        Some(Stmt::Return(ReturnStmt {
+            token: return_stmt.token.clone(),
+            value: return_stmt.value.accept(self),
+        }))
+    }
+
+    fn visit_break(&mut self, break_stmt: &BreakStmt) -> Option<Stmt> {
+        Some(Stmt::Break(break_stmt.clone()))
+    }
+
+    fn visit_continue(&mut self, continue_stmt: &ContinueStmt) -> Option<Stmt> {
+        Some(Stmt::Continue(continue_stmt.clone()))
+    }
+
+    fn visit_throw(&mut self, throw_stmt: &ThrowStmt) -> Option<Stmt> {
+        Some(Stmt::Throw(ThrowStmt {
+            token: throw_stmt.token.clone(),
+            value: throw_stmt.value.accept(self),
+        }))
+    }
+
+    fn visit_try(&mut self, try_stmt: &TryStmt) -> Option<Stmt> {
+        Some(Stmt::Try(TryStmt {
+            body: self.optimize_block(&try_stmt.body),
+            catch: try_stmt.catch.as_ref().map(|catch| CatchClause {
+                name: catch.name.clone(),
+                body: self.optimize_block(&catch.body),
+            }),
+            finally: try_stmt
+                .finally
+                .as_ref()
+                .map(|finally| self.optimize_block(finally)),
+        }))
+    }
+
+    fn visit_import(&mut self, import_stmt: &ImportStmt) -> Option<Stmt> {
+        Some(Stmt::Import(import_stmt.clone()))
+    }
+}
+
+impl ExprVisitor<Expr> for Optimizer {
+    fn visit_expr(&mut self, expr: &Expr) -> Expr {
+        match expr {
+            Expr::Binary(binary) => self.visit_binary_expr(binary),
+            Expr::Grouping(grouping) => self.visit_grouping_expr(grouping),
+            Expr::Literal(lit) => self.visit_literal_expr(lit),
+            Expr::Unary(unary) => self.visit_unary_expr(unary),
+            Expr::Variable(variable) => self.visit_variable_expr(variable),
+            Expr::Assign(assign) => self.visit_assign_expr(assign),
+            Expr::DestructureAssign(destructure) => self.visit_destructure_assign_expr(destructure),
+            Expr::Logical(logical) => self.visit_logical_expr(logical),
+            Expr::Call(call) => self.visit_call_expr(call),
+            Expr::List(list) => self.visit_list_expr(list),
+            Expr::Index(index) => self.visit_index_expr(index),
+            Expr::IndexSet(index_set) => self.visit_index_set_expr(index_set),
+            Expr::Map(map) => self.visit_map_expr(map),
+            Expr::Lambda(lambda) => self.visit_lambda_expr(lambda),
+            Expr::Get(get) => self.visit_get_expr(get),
+            Expr::Interpolation(interpolation) => self.visit_interpolation_expr(interpolation),
+            Expr::OptionalChain(chain) => self.visit_optional_chain_expr(chain),
+            Expr::Update(update) => self.visit_update_expr(update),
+            Expr::Conditional(conditional) => self.visit_conditional_expr(conditional),
+        }
+    }
+
+    fn visit_binary_expr(&mut self, binary_expr: &Binary) -> Expr {
+        let left = binary_expr.left.accept(self);
+        let right = binary_expr.right.accept(self);
+        let constant = matches!((&left, &right), (Expr::Literal(_), Expr::Literal(_)));
+
+        let binary = Expr::Binary(Binary {
+            left: Box::new(left),
+            right: Box::new(right),
+            operator: binary_expr.operator.clone(),
+        });
+
+        if constant {
+            return self.fold(binary);
+        }
+        binary
+    }
+
+    fn visit_grouping_expr(&mut self, grouping_expr: &Grouping) -> Expr {
+        match grouping_expr.expression.accept(self) {
+            Expr::Literal(lit) => Expr::Literal(lit),
+            expression => Expr::Grouping(Grouping {
+                expression: Box::new(expression),
+            }),
+        }
+    }
+
+    fn visit_literal_expr(&mut self, literal_expr: &Lit) -> Expr {
+        Expr::Literal(literal_expr.clone())
+    }
+
+    fn visit_unary_expr(&mut self, unary_expr: &Unary) -> Expr {
+        let right = unary_expr.right.accept(self);
+
+        match right {
+            Expr::Literal(_) => self.fold(Expr::Unary(Unary {
+                operator: unary_expr.operator.clone(),
+                right: Box::new(right),
+            })),
+            // `!!x` is only `x` itself when `x` already evaluates to a boolean
+            Expr::Unary(inner)
+                if unary_expr.operator.ty == TokenType::Bang
+                    && inner.operator.ty == TokenType::Bang
+                    && Self::is_boolean(&inner.right) =>
+            {
+                *inner.right
+            }
+            right => Expr::Unary(Unary {
+                operator: unary_expr.operator.clone(),
+                right: Box::new(right),
+            }),
+        }
+    }
+
+    fn visit_variable_expr(&mut self, variable_expr: &Variable) -> Expr {
+        Expr::Variable(variable_expr.clone())
+    }
+
+    fn visit_assign_expr(&mut self, assign_expr: &Assign) -> Expr {
+        Expr::Assign(Assign {
+            token: assign_expr.token.clone(),
+            value: Box::new(assign_expr.value.accept(self)),
+        })
+    }
+
+    fn visit_destructure_assign_expr(&mut self, destructure_expr: &DestructureAssign) -> Expr {
+        Expr::DestructureAssign(DestructureAssign {
+            pattern: self.optimize_pattern(&destructure_expr.pattern),
+            value: Box::new(destructure_expr.value.accept(self)),
+        })
+    }
+
+    fn visit_logical_expr(&mut self, logical_expr: &Logical) -> Expr {
+        let left = logical_expr.left.accept(self);
+        let right = logical_expr.right.accept(self);
+
+        match (
+            logical_expr.operator.ty.clone(),
+            Self::constant_truthiness(&left),
+        ) {
+            (TokenType::Or, Some(true)) | (TokenType::And, Some(false)) => left,
+            (TokenType::Or, Some(false)) | (TokenType::And, Some(true)) => right,
+            (TokenType::QuestionQuestion, _) => match &left {
+                Expr::Literal(Lit {
+                    literal: Literal::Nil,
+                }) => right,
+                Expr::Literal(_) => left,
+                _ => Expr::Logical(Logical {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator: logical_expr.operator.clone(),
+                }),
+            },
+            _ => Expr::Logical(Logical {
+                left: Box::new(left),
+                right: Box::new(right),
+                operator: logical_expr.operator.clone(),
+            }),
+        }
+    }
+
+    fn visit_call_expr(&mut self, call_expr: &Call) -> Expr {
+        Expr::Call(Call {
+            callee: Box::new(call_expr.callee.accept(self)),
+            paren: call_expr.paren.clone(),
+            arguments: call_expr
+                .arguments
+                .iter()
+                .map(|argument| argument.accept(self))
+                .collect(),
+            named: call_expr
+                .named
+                .iter()
+                .map(|(name, argument)| (name.clone(), argument.accept(self)))
+                .collect(),
+        })
+    }
+
+    fn visit_list_expr(&mut self, list_expr: &List) -> Expr {
+        Expr::List(List {
+            bracket: list_expr.bracket.clone(),
+            elements: list_expr
+                .elements
+                .iter()
+                .map(|element| element.accept(self))
+                .collect(),
+        })
+    }
+
+    fn visit_map_expr(&mut self, map_expr: &Map) -> Expr {
+        Expr::Map(Map {
+            brace: map_expr.brace.clone(),
+            entries: map_expr
+                .entries
+                .iter()
+                .map(|(key, value)| (key.accept(self), value.accept(self)))
+                .collect(),
+        })
+    }
+
+    fn visit_index_expr(&mut self, index_expr: &Index) -> Expr {
+        Expr::Index(Index {
+            object: Box::new(index_expr.object.accept(self)),
+            bracket: index_expr.bracket.clone(),
+            index: Box::new(index_expr.index.accept(self)),
+        })
+    }
+
+    fn visit_index_set_expr(&mut self, index_set_expr: &IndexSet) -> Expr {
+        Expr::IndexSet(IndexSet {
+            object: Box::new(index_set_expr.object.accept(self)),
+            bracket: index_set_expr.bracket.clone(),
+            index: Box::new(index_set_expr.index.accept(self)),
+            value: Box::new(index_set_expr.value.accept(self)),
+        })
+    }
+
+    fn visit_lambda_expr(&mut self, lambda_expr: &Lambda) -> Expr {
+        Expr::Lambda(Lambda {
+            function: self.optimize_function(&lambda_expr.function),
+        })
+    }
+
+    fn visit_get_expr(&mut self, get_expr: &Get) -> Expr {
+        Expr::Get(Get {
+            object: Box::new(get_expr.object.accept(self)),
+            name: get_expr.name.clone(),
+            optional: get_expr.optional,
+        })
+    }
+
+    fn visit_interpolation_expr(&mut self, interpolation_expr: &Interpolation) -> Expr {
+        Expr::Interpolation(Interpolation {
+            parts: interpolation_expr
+                .parts
+                .iter()
+                .map(|part| part.accept(self))
+                .collect(),
+        })
+    }
+
+    fn visit_optional_chain_expr(&mut self, chain_expr: &OptionalChain) -> Expr {
+        Expr::OptionalChain(OptionalChain {
+            expression: Box::new(chain_expr.expression.accept(self)),
+        })
+    }
+
+    fn visit_update_expr(&mut self, update_expr: &Update) -> Expr {
+        Expr::Update(Update {
+            target: Box::new(update_expr.target.accept(self)),
+            operator: update_expr.operator.clone(),
+            value: Box::new(update_expr.value.accept(self)),
+            postfix: update_expr.postfix,
+        })
+    }
+
+    fn visit_conditional_expr(&mut self, conditional_expr: &Conditional) -> Expr {
+        let condition = conditional_expr.condition.accept(self);
+        match Self::constant_truthiness(&condition) {
+            Some(true) => conditional_expr.then_branch.accept(self),
+            Some(false) => conditional_expr.else_branch.accept(self),
+            None => Expr::Conditional(Conditional {
+                condition: Box::new(condition),
+                question: conditional_expr.question.clone(),
+                then_branch: Box::new(conditional_expr.then_branch.accept(self)),
+                else_branch: Box::new(conditional_expr.else_branch.accept(self)),
+            }),
+        }
+    }
+}
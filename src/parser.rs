@@ -1,8 +1,11 @@
 use crate::{
     ast::nodes::{
-        Assign, Binary, BlockStmt, Call, Expr, ExpressionStmt, ForStmt, FunctionStmt, Grouping,
-        IfStmt, Lit, Logical, PrintStmt, ReturnStmt, Stmt, Unary, Variable,
-        VariableDeclarationStmt, WhileStmt,
+        self, Assign, Binary, BlockStmt, BreakStmt, Call, CatchClause, Conditional, ContinueStmt,
+        DeclarationKind, DestructureAssign, DestructureStmt, Expr, ExpressionStmt, ForInStmt,
+        ForStmt, FunctionStmt, Get, Grouping, IfStmt, ImportStmt, Index, IndexSet, Iterable,
+        Lambda, List, Lit, Logical, Map, OptionalChain, Param, Pattern, PatternElement, PrintStmt,
+        ReturnStmt, Stmt, ThrowStmt, TryStmt, Unary, Update, Variable, VariableDeclarationStmt,
+        WhileStmt,
     },
     error::LoxError,
     literal::Literal,
@@ -28,14 +31,11 @@ impl<'a> Parser<'a> {
         while !self.is_at_end() {
             match self.declaration() {
                 Ok(statement) => statements.push(statement),
-                Err(error) => {
-                    error.log();
-                    errors.push(error);
-                }
+                Err(error) => errors.push(error),
             }
         }
 
-        if errors.len() > 0 {
+        if !errors.is_empty() {
             return Err(errors);
         }
 
@@ -43,10 +43,21 @@ impl<'a> Parser<'a> {
     }
 
     fn declaration(&mut self) -> Result<Stmt, LoxError> {
-        let result = if self.match_token(Fun) {
+        let start = self.current;
+        // `fun` followed by anything but a name starts an anonymous function expression
+        let result = if self.check(Fun) && self.check_next(Identifier) {
+            self.advance();
             self.function(String::from("function"))
+        } else if self.match_token(Import) {
+            self.import_declaration()
+        } else if self.match_token(From) {
+            self.selective_import_declaration()
         } else if self.match_token(Var) {
-            self.variable_declaration()
+            self.variable_declaration(DeclarationKind::Var)
+        } else if self.match_token(Let) {
+            self.variable_declaration(DeclarationKind::Let)
+        } else if self.match_token(Const) {
+            self.variable_declaration(DeclarationKind::Const)
         } else {
             self.statement()
         };
@@ -54,6 +65,11 @@ impl<'a> Parser<'a> {
         match result {
             Ok(declaration) => Ok(declaration),
             Err(error) => {
+                // skip at least the token the error was found at, or the
+                // next declaration would fail on it again
+                if self.current == start {
+                    self.advance();
+                }
                 self.synchronize();
                 Err(error)
             }
@@ -68,14 +84,43 @@ impl<'a> Parser<'a> {
 
         self.consume(LeftParen, format!("Expect ( after {} name.", kind).as_str())?;
 
-        let mut params: Vec<Token> = vec![];
+        Ok(Stmt::Function(self.function_body(name, &kind)?))
+    }
+
+    // `a, b = 10, ...rest)`, after the opening paren
+    fn parameters(&mut self) -> Result<(Vec<Param>, Option<Token>), LoxError> {
+        let mut params: Vec<Param> = vec![];
+        let mut rest = None;
         if !self.check(RightParen) {
             loop {
                 if params.len() > 255 {
                     self.error("Can't have more than 255 characters.");
                 }
 
-                params.push(self.consume(Identifier, "Expect parameter name.")?.clone());
+                if self.match_token(DotDotDot) {
+                    rest = Some(
+                        self.consume(Identifier, "Expect rest parameter name.")?
+                            .clone(),
+                    );
+                    if self.check(Comma) {
+                        return Err(self.error("Rest parameter must be last."));
+                    }
+                    break;
+                }
+
+                let name = self.consume(Identifier, "Expect parameter name.")?.clone();
+                let default = if self.match_token(Equal) {
+                    Some(self.expression()?)
+                } else if params.iter().any(|param| param.default.is_some()) {
+                    return Err(LoxError::Parser {
+                        token: name,
+                        message: "Parameter without a default can't follow one with a default."
+                            .into(),
+                    });
+                } else {
+                    None
+                };
+                params.push(Param { name, default });
 
                 if !self.match_token(Comma) {
                     break;
@@ -84,6 +129,12 @@ impl<'a> Parser<'a> {
         }
 
         self.consume(RightParen, "Expect ')' after parameters.")?;
+        Ok((params, rest))
+    }
+
+    // parameters and body of a function, after its opening paren
+    fn function_body(&mut self, name: Box<Token>, kind: &str) -> Result<FunctionStmt, LoxError> {
+        let (params, rest) = self.parameters()?;
 
         self.consume(
             LeftBrace,
@@ -92,10 +143,121 @@ impl<'a> Parser<'a> {
 
         let body = self.block()?;
 
-        return Ok(Stmt::Function(FunctionStmt { name, params, body }));
+        Ok(FunctionStmt {
+            name,
+            params,
+            rest,
+            body,
+        })
+    }
+
+    // Collects the pieces of `"a ${b} c"`, starting after its first `"a ${`.
+    fn interpolation(&mut self) -> Result<Expr, LoxError> {
+        let mut parts: Vec<Expr> = vec![];
+        loop {
+            let string = self.previous().clone();
+            if string.literal != Literal::String("".into()) {
+                parts.push(Expr::Literal(Lit {
+                    literal: string.literal.clone(),
+                }));
+            }
+            if string.ty == StringLit {
+                break;
+            }
+
+            // the rest of the string, which starts at the `}`, straight after
+            // the `${` means there is nothing to interpolate
+            let next = self.peek();
+            if matches!(next.ty, Interpolation | StringLit) && next.lexeme.starts_with('}') {
+                return Err(LoxError::Parser {
+                    token: Token::new(Interpolation, None, Some("${}".into()), Some(string.line)),
+                    message: "Empty interpolation.".into(),
+                });
+            }
+
+            parts.push(self.expression()?);
+
+            if !self.match_token(Interpolation) && !self.match_token(StringLit) {
+                return Err(self.error("Expect '}' after interpolated expression."));
+            }
+        }
+
+        Ok(Expr::Interpolation(nodes::Interpolation { parts }))
+    }
+
+    fn anonymous_name(&self) -> Box<Token> {
+        let keyword = self.previous();
+        Box::new(Token::new(
+            Identifier,
+            None,
+            Some("anonymous".into()),
+            Some(keyword.line),
+        ))
+    }
+
+    // Whether the tokens from the current '(' form an arrow function's
+    // parameter list, `(a, b = 1) =>`, rather than a grouping. Defaults can
+    // hold any expression, so this finds the matching ')' and looks past it.
+    fn is_arrow_function(&self) -> bool {
+        let mut depth = 0;
+        for (index, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.ty {
+                LeftParen | LeftBracket | LeftBrace => depth += 1,
+                RightParen | RightBracket | RightBrace => depth -= 1,
+                EOF => return false,
+                _ => {}
+            }
+            if depth == 0 {
+                return self.tokens[index + 1].ty == Arrow;
+            }
+        }
+        false
+    }
+
+    fn arrow_function(&mut self) -> Result<Expr, LoxError> {
+        self.consume(LeftParen, "Expect '(' before parameters.")?;
+        let name = self.anonymous_name();
+        let (params, rest) = self.parameters()?;
+
+        let arrow = self
+            .consume(Arrow, "Expect '=>' after parameters.")?
+            .clone();
+        // a braced body is a block, as for `fun`, so returning a map takes
+        // parentheses: `() => ({"a": 1})`
+        let body = if self.match_token(LeftBrace) {
+            self.block()?
+        } else {
+            let value = self.assignment()?;
+            vec![Stmt::Return(ReturnStmt {
+                token: arrow,
+                value,
+            })]
+        };
+
+        Ok(Expr::Lambda(Lambda {
+            function: FunctionStmt {
+                name,
+                params,
+                rest,
+                body,
+            },
+        }))
     }
 
-    fn variable_declaration(&mut self) -> Result<Stmt, LoxError> {
+    fn variable_declaration(&mut self, kind: DeclarationKind) -> Result<Stmt, LoxError> {
+        if self.check(LeftBracket) || self.check(LeftBrace) {
+            let pattern = self.pattern()?;
+            self.consume(Equal, "Expect '=' after destructuring pattern.")?;
+            let initializer = self.expression()?;
+            self.consume(SemiColon, "Expect ; after variable declaration.")?;
+
+            return Ok(Stmt::Destructure(DestructureStmt {
+                pattern,
+                initializer,
+                kind,
+            }));
+        }
+
         if self.match_token(Identifier) {
             let token = self.previous().clone();
             let mut initializer: Expr = Expr::Literal(Lit {
@@ -104,6 +266,8 @@ impl<'a> Parser<'a> {
 
             if self.match_token(Equal) {
                 initializer = self.expression()?;
+            } else if kind == DeclarationKind::Const {
+                return Err(self.error("Expect '=' after constant name."));
             }
 
             if !self.match_token(SemiColon) {
@@ -113,12 +277,105 @@ impl<'a> Parser<'a> {
             return Ok(Stmt::Variable(VariableDeclarationStmt {
                 token,
                 initializer,
+                kind,
             }));
         }
 
         self.statement()
     }
 
+    // `[a, [b, c] = pair]` or `{x, y: [a, b], z = 0}` in a declaration
+    fn pattern(&mut self) -> Result<Pattern, LoxError> {
+        if self.match_token(LeftBracket) {
+            let bracket = self.previous().clone();
+            let mut elements = vec![];
+            if !self.check(RightBracket) {
+                loop {
+                    let target = self.pattern()?;
+                    elements.push(self.pattern_element(target)?);
+                    if !self.match_token(Comma) {
+                        break;
+                    }
+                }
+            }
+            self.consume(RightBracket, "Expect ']' after list pattern.")?;
+            return Ok(Pattern::List { bracket, elements });
+        }
+
+        if self.match_token(LeftBrace) {
+            let brace = self.previous().clone();
+            let mut entries = vec![];
+            if !self.check(RightBrace) {
+                loop {
+                    let key = self.consume(Identifier, "Expect key name.")?.clone();
+                    let target = if self.match_token(Colon) {
+                        self.pattern()?
+                    } else {
+                        Pattern::Name(key.clone())
+                    };
+                    entries.push((key, self.pattern_element(target)?));
+                    if !self.match_token(Comma) {
+                        break;
+                    }
+                }
+            }
+            self.consume(RightBrace, "Expect '}' after map pattern.")?;
+            return Ok(Pattern::Map { brace, entries });
+        }
+
+        let name = self.consume(Identifier, "Expect variable name.")?.clone();
+        Ok(Pattern::Name(name))
+    }
+
+    fn pattern_element(&mut self, target: Pattern) -> Result<PatternElement, LoxError> {
+        let default = if self.match_token(Equal) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        Ok(PatternElement { target, default })
+    }
+
+    // Reinterprets a list literal on the left of `=` as a pattern. Elements
+    // parsed as assignments, `[a = 1]` or `[xs[0] = 1]`, give the default for
+    // their target.
+    fn assignment_pattern(&self, target: Expr) -> Result<PatternElement, LoxError> {
+        let (target, default) = match target {
+            Expr::Variable(variable) => (Pattern::Name(variable.token), None),
+            Expr::Assign(assign) => (Pattern::Name(assign.token), Some(*assign.value)),
+            Expr::Index(index) => (Pattern::Index(index), None),
+            Expr::IndexSet(index_set) => (
+                Pattern::Index(Index {
+                    object: index_set.object,
+                    bracket: index_set.bracket,
+                    index: index_set.index,
+                }),
+                Some(*index_set.value),
+            ),
+            Expr::List(list) => (self.list_pattern(list)?, None),
+            Expr::DestructureAssign(destructure) => (destructure.pattern, Some(*destructure.value)),
+            _ => {
+                return Err(LoxError::Parser {
+                    token: self.previous().clone(),
+                    message: "Invalid assignment target.".into(),
+                })
+            }
+        };
+        Ok(PatternElement { target, default })
+    }
+
+    fn list_pattern(&self, list: List) -> Result<Pattern, LoxError> {
+        let elements = list
+            .elements
+            .into_iter()
+            .map(|element| self.assignment_pattern(element))
+            .collect::<Result<_, _>>()?;
+        Ok(Pattern::List {
+            bracket: list.bracket,
+            elements,
+        })
+    }
+
     fn statement(&mut self) -> Result<Stmt, LoxError> {
         if self.match_token(For) {
             return self.for_statement();
@@ -140,6 +397,29 @@ impl<'a> Parser<'a> {
             return self.while_statement();
         }
 
+        if self.match_token(Break) {
+            let token = self.previous().clone();
+            self.consume(SemiColon, "Expect ';' after 'break'.")?;
+            return Ok(Stmt::Break(BreakStmt { token }));
+        }
+
+        if self.match_token(Continue) {
+            let token = self.previous().clone();
+            self.consume(SemiColon, "Expect ';' after 'continue'.")?;
+            return Ok(Stmt::Continue(ContinueStmt { token }));
+        }
+
+        if self.match_token(Throw) {
+            let token = self.previous().clone();
+            let value = self.expression()?;
+            self.consume(SemiColon, "Expect ';' after thrown value.")?;
+            return Ok(Stmt::Throw(ThrowStmt { token, value }));
+        }
+
+        if self.match_token(Try) {
+            return self.try_statement();
+        }
+
         if self.match_token(LeftBrace) {
             return Ok(Stmt::Block(BlockStmt {
                 statements: self.block()?,
@@ -167,13 +447,24 @@ impl<'a> Parser<'a> {
             return Err(self.error("Expect '(' after 'if'."));
         }
 
+        if (self.check(Var) || self.check(Let) || self.check(Const))
+            && self.check_next(Identifier)
+            && self.check_at(2, In)
+        {
+            return self.for_in_statement();
+        }
+
         let mut initializer: Option<Box<Stmt>> = None;
         let mut condition: Option<Expr> = None;
         let mut increment: Option<Expr> = None;
 
         if self.match_token(SemiColon) {
         } else if self.match_token(Var) {
-            initializer = Some(Box::new(self.variable_declaration()?));
+            initializer = Some(Box::new(self.variable_declaration(DeclarationKind::Var)?));
+        } else if self.match_token(Let) {
+            initializer = Some(Box::new(self.variable_declaration(DeclarationKind::Let)?));
+        } else if self.match_token(Const) {
+            initializer = Some(Box::new(self.variable_declaration(DeclarationKind::Const)?));
         } else {
             initializer = Some(Box::new(self.expression_statement()?));
         }
@@ -202,6 +493,39 @@ impl<'a> Parser<'a> {
         }))
     }
 
+    // `for (var name in iterable) body`, or with `let` or `const`, after the
+    // opening paren
+    fn for_in_statement(&mut self) -> Result<Stmt, LoxError> {
+        // `var name in`, already checked by the caller
+        let kind = match self.advance().ty {
+            Let => DeclarationKind::Let,
+            Const => DeclarationKind::Const,
+            _ => DeclarationKind::Var,
+        };
+        let name = self.advance().clone();
+        let keyword = self.advance().clone();
+
+        let start = self.expression()?;
+        let iterable = if self.match_token(DotDot) || self.match_token(DotDotEqual) {
+            Iterable::Range {
+                start,
+                operator: self.previous().clone(),
+                end: self.expression()?,
+            }
+        } else {
+            Iterable::Value(start)
+        };
+        self.consume(RightParen, "Expect ')' after for clauses.")?;
+
+        Ok(Stmt::ForIn(ForInStmt {
+            name,
+            kind,
+            keyword,
+            iterable,
+            body: Box::new(self.statement()?),
+        }))
+    }
+
     fn if_statement(&mut self) -> Result<Stmt, LoxError> {
         if !self.match_token(LeftParen) {
             return Err(self.error("Expect '(' after 'if'."));
@@ -223,11 +547,11 @@ impl<'a> Parser<'a> {
             }));
         }
 
-        return Ok(Stmt::If(IfStmt {
+        Ok(Stmt::If(IfStmt {
             condition,
             then_branch,
             else_branch: None,
-        }));
+        }))
     }
 
     fn print_statement(&mut self) -> Result<Stmt, LoxError> {
@@ -254,6 +578,84 @@ impl<'a> Parser<'a> {
         Ok(Stmt::Return(ReturnStmt { token, value }))
     }
 
+    fn import_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        let path = self
+            .consume(StringLit, "Expect module path after 'import'.")?
+            .clone();
+        self.consume(As, "Expect 'as' after module path.")?;
+        let alias = self
+            .consume(Identifier, "Expect module name after 'as'.")?
+            .clone();
+        self.consume(SemiColon, "Expect ';' after import.")?;
+
+        Ok(Stmt::Import(ImportStmt {
+            keyword,
+            path,
+            alias: Some(alias),
+            names: vec![],
+        }))
+    }
+
+    fn selective_import_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        let path = self
+            .consume(StringLit, "Expect module path after 'from'.")?
+            .clone();
+        self.consume(Import, "Expect 'import' after module path.")?;
+
+        let mut names: Vec<Token> = vec![];
+        loop {
+            names.push(self.consume(Identifier, "Expect name to import.")?.clone());
+            if !self.match_token(Comma) {
+                break;
+            }
+        }
+        self.consume(SemiColon, "Expect ';' after import.")?;
+
+        Ok(Stmt::Import(ImportStmt {
+            keyword,
+            path,
+            alias: None,
+            names,
+        }))
+    }
+
+    fn try_statement(&mut self) -> Result<Stmt, LoxError> {
+        self.consume(LeftBrace, "Expect '{' after 'try'.")?;
+        let body = self.block()?;
+
+        let mut catch = None;
+        if self.match_token(Catch) {
+            self.consume(LeftParen, "Expect '(' after 'catch'.")?;
+            let name = self
+                .consume(Identifier, "Expect error variable name.")?
+                .clone();
+            self.consume(RightParen, "Expect ')' after error variable.")?;
+            self.consume(LeftBrace, "Expect '{' before catch body.")?;
+            catch = Some(CatchClause {
+                name,
+                body: self.block()?,
+            });
+        }
+
+        let mut finally = None;
+        if self.match_token(Finally) {
+            self.consume(LeftBrace, "Expect '{' after 'finally'.")?;
+            finally = Some(self.block()?);
+        }
+
+        if catch.is_none() && finally.is_none() {
+            return Err(self.error("Expect 'catch' or 'finally' after try block."));
+        }
+
+        Ok(Stmt::Try(TryStmt {
+            body,
+            catch,
+            finally,
+        }))
+    }
+
     fn while_statement(&mut self) -> Result<Stmt, LoxError> {
         if !self.match_token(LeftParen) {
             return Err(self.error("Expect '(' after 'if'."));
@@ -265,10 +667,10 @@ impl<'a> Parser<'a> {
             return Err(self.error("Expect ')' after 'if'."));
         }
 
-        return Ok(Stmt::While(WhileStmt {
+        Ok(Stmt::While(WhileStmt {
             condition,
             body: Box::new(self.statement()?),
-        }));
+        }))
     }
 
     fn expression_statement(&mut self) -> Result<Stmt, LoxError> {
@@ -285,26 +687,134 @@ impl<'a> Parser<'a> {
     }
 
     fn assignment(&mut self) -> Result<Expr, LoxError> {
-        let left = self.or();
+        let left = self.conditional();
 
         if self.match_token(Equal) {
             let value = self.assignment()?;
 
-            if let Ok(expr) = &left {
-                if let Expr::Variable(variable) = expr {
+            match left {
+                Ok(Expr::Variable(variable)) => {
                     return Ok(Expr::Assign(Assign {
-                        token: variable.token.clone(),
+                        token: variable.token,
                         value: Box::new(value),
                     }));
                 }
+                Ok(Expr::Index(index)) => {
+                    return Ok(Expr::IndexSet(IndexSet {
+                        object: index.object,
+                        bracket: index.bracket,
+                        index: index.index,
+                        value: Box::new(value),
+                    }));
+                }
+                Ok(Expr::List(list)) => {
+                    return Ok(Expr::DestructureAssign(DestructureAssign {
+                        pattern: self.list_pattern(list)?,
+                        value: Box::new(value),
+                    }));
+                }
+                _ => {}
             }
 
             return Err(self.error("Invalid assignment target."));
         }
 
+        if self.match_token(PlusEqual)
+            || self.match_token(MinusEqual)
+            || self.match_token(StarEqual)
+            || self.match_token(SlashEqual)
+            || self.match_token(PercentEqual)
+        {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+            return self.update(left?, operator, value, false);
+        }
+
         left
     }
 
+    // Builds a compound assignment, checking the target can be assigned to.
+    fn update(
+        &mut self,
+        target: Expr,
+        mut operator: Token,
+        value: Expr,
+        postfix: bool,
+    ) -> Result<Expr, LoxError> {
+        if !matches!(target, Expr::Variable(_) | Expr::Index(_)) {
+            return Err(LoxError::Parser {
+                token: operator,
+                message: "Invalid assignment target.".into(),
+            });
+        }
+
+        operator.ty = match operator.ty {
+            PlusEqual | PlusPlus => Plus,
+            MinusEqual | MinusMinus => Minus,
+            StarEqual => Star,
+            SlashEqual => Slash,
+            _ => Percent,
+        };
+        Ok(Expr::Update(Update {
+            target: Box::new(target),
+            operator,
+            value: Box::new(value),
+            postfix,
+        }))
+    }
+
+    // `++x` and `x++` add one to `x`, like `x += 1`
+    fn increment(
+        &mut self,
+        operator: Token,
+        target: Expr,
+        postfix: bool,
+    ) -> Result<Expr, LoxError> {
+        let one = Expr::Literal(Lit {
+            literal: Literal::Integer(1),
+        });
+        self.update(target, operator, one, postfix)
+    }
+
+    fn conditional(&mut self) -> Result<Expr, LoxError> {
+        let condition = self.coalesce()?;
+
+        if self.match_token(Question) {
+            let question = self.previous().clone();
+            let then_branch = self.expression()?;
+            self.consume(
+                Colon,
+                "Expect ':' after then branch of conditional expression.",
+            )?;
+            let else_branch = self.conditional()?;
+
+            return Ok(Expr::Conditional(Conditional {
+                condition: Box::new(condition),
+                question,
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            }));
+        }
+
+        Ok(condition)
+    }
+
+    fn coalesce(&mut self) -> Result<Expr, LoxError> {
+        let mut left = self.or()?;
+
+        while self.match_token(QuestionQuestion) {
+            let operator = self.previous().clone();
+            let right = self.or()?;
+            left = Expr::Logical(Logical {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            });
+        }
+
+        Ok(left)
+    }
+
     fn or(&mut self) -> Result<Expr, LoxError> {
         let mut left = self.and();
 
@@ -338,8 +848,49 @@ impl<'a> Parser<'a> {
     }
 
     fn equality(&mut self) -> Result<Expr, LoxError> {
-        let mut left = self.comparison()?;
+        let mut left = self.bit_or()?;
         while self.match_token(EqualEqual) || self.match_token(BangEqual) {
+            left = Expr::Binary(Binary {
+                left: Box::new(left),
+                operator: self.previous().clone(),
+                right: Box::new(self.bit_or()?),
+            })
+        }
+
+        Ok(left)
+    }
+
+    // `&`, `^` and `|` bind tighter than equality, so `flags & MASK == 0`
+    // tests the masked bits rather than masking a boolean.
+    fn bit_or(&mut self) -> Result<Expr, LoxError> {
+        let mut left = self.bit_xor()?;
+        while self.match_token(Pipe) {
+            left = Expr::Binary(Binary {
+                left: Box::new(left),
+                operator: self.previous().clone(),
+                right: Box::new(self.bit_xor()?),
+            })
+        }
+
+        Ok(left)
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr, LoxError> {
+        let mut left = self.bit_and()?;
+        while self.match_token(Caret) {
+            left = Expr::Binary(Binary {
+                left: Box::new(left),
+                operator: self.previous().clone(),
+                right: Box::new(self.bit_and()?),
+            })
+        }
+
+        Ok(left)
+    }
+
+    fn bit_and(&mut self) -> Result<Expr, LoxError> {
+        let mut left = self.comparison()?;
+        while self.match_token(Ampersand) {
             left = Expr::Binary(Binary {
                 left: Box::new(left),
                 operator: self.previous().clone(),
@@ -351,12 +902,25 @@ impl<'a> Parser<'a> {
     }
 
     fn comparison(&mut self) -> Result<Expr, LoxError> {
-        let mut left = self.term()?;
+        let mut left = self.shift()?;
         while self.match_token(Greater)
             || self.match_token(GreaterEqual)
             || self.match_token(Less)
             || self.match_token(LessEqual)
         {
+            left = Expr::Binary(Binary {
+                left: Box::new(left),
+                operator: self.previous().clone(),
+                right: Box::new(self.shift()?),
+            })
+        }
+
+        Ok(left)
+    }
+
+    fn shift(&mut self) -> Result<Expr, LoxError> {
+        let mut left = self.term()?;
+        while self.match_token(LessLess) || self.match_token(GreaterGreater) {
             left = Expr::Binary(Binary {
                 left: Box::new(left),
                 operator: self.previous().clone(),
@@ -382,7 +946,11 @@ impl<'a> Parser<'a> {
 
     fn factor(&mut self) -> Result<Expr, LoxError> {
         let mut left = self.unary()?;
-        while self.match_token(Star) || self.match_token(Slash) {
+        while self.match_token(Star)
+            || self.match_token(Slash)
+            || self.match_token(Percent)
+            || self.match_token(TildeSlash)
+        {
             left = Expr::Binary(Binary {
                 left: Box::new(left),
                 operator: self.previous().clone(),
@@ -394,30 +962,79 @@ impl<'a> Parser<'a> {
     }
 
     fn unary(&mut self) -> Result<Expr, LoxError> {
-        if self.match_token(Bang) || self.match_token(Minus) {
+        if self.match_token(PlusPlus) || self.match_token(MinusMinus) {
+            let operator = self.previous().clone();
+            let target = self.unary()?;
+            return self.increment(operator, target, false);
+        }
+
+        if self.match_token(Bang) || self.match_token(Minus) || self.match_token(Tilde) {
             return Ok(Expr::Unary(Unary {
                 operator: self.previous().clone(),
                 right: Box::new(self.unary()?),
             }));
         }
 
-        self.call()
+        self.power()
+    }
+
+    // `**` binds tighter than a unary minus on its left and groups to the right,
+    // so `-2 ** 2` is `-(2 ** 2)` and `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    fn power(&mut self) -> Result<Expr, LoxError> {
+        let base = self.postfix()?;
+        if self.match_token(StarStar) {
+            return Ok(Expr::Binary(Binary {
+                left: Box::new(base),
+                operator: self.previous().clone(),
+                right: Box::new(self.unary()?),
+            }));
+        }
+
+        Ok(base)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, LoxError> {
+        let expr = self.call()?;
+        if self.match_token(PlusPlus) || self.match_token(MinusMinus) {
+            let operator = self.previous().clone();
+            return self.increment(operator, expr, true);
+        }
+
+        Ok(expr)
     }
 
     fn call(&mut self) -> Result<Expr, LoxError> {
         let mut expr = self.primary()?;
+        let mut optional = false;
 
         // Creates nodes for consicutive calls as well.
         loop {
             if self.match_token(LeftParen) {
                 let mut arguments = vec![];
+                let mut named: Vec<(Token, Expr)> = vec![];
                 if !self.check(RightParen) {
                     if arguments.len() >= 255 {
                         return Err(self.error("Can't have more than 255 arguments"));
                     }
 
                     loop {
-                        arguments.push(self.expression()?);
+                        if self.check(Identifier) && self.check_next(Colon) {
+                            let name = self.advance().clone();
+                            self.advance();
+                            if named.iter().any(|(other, _)| other.lexeme == name.lexeme) {
+                                return Err(LoxError::Parser {
+                                    message: format!("Duplicate argument '{}'.", name.lexeme),
+                                    token: name,
+                                });
+                            }
+                            named.push((name, self.expression()?));
+                        } else if !named.is_empty() {
+                            return Err(
+                                self.error("Positional arguments must come before named ones.")
+                            );
+                        } else {
+                            arguments.push(self.expression()?);
+                        }
                         if !self.match_token(Comma) {
                             break;
                         }
@@ -431,14 +1048,48 @@ impl<'a> Parser<'a> {
                 expr = Expr::Call(Call {
                     callee: Box::new(expr),
                     arguments,
+                    named,
                     paren: self.previous().clone(),
                 });
 
                 continue;
             }
+
+            if self.match_token(Dot) || self.match_token(QuestionDot) {
+                let is_optional = self.previous().ty == QuestionDot;
+                optional |= is_optional;
+                let name = self
+                    .consume(Identifier, "Expect property name after '.'.")?
+                    .clone();
+                expr = Expr::Get(Get {
+                    object: Box::new(expr),
+                    name,
+                    optional: is_optional,
+                });
+
+                continue;
+            }
+
+            if self.match_token(LeftBracket) {
+                let index = self.expression()?;
+                self.consume(RightBracket, "Expect ']' after index.")?;
+
+                expr = Expr::Index(Index {
+                    object: Box::new(expr),
+                    bracket: self.previous().clone(),
+                    index: Box::new(index),
+                });
+
+                continue;
+            }
             break;
         }
 
+        if optional {
+            expr = Expr::OptionalChain(OptionalChain {
+                expression: Box::new(expr),
+            });
+        }
         Ok(expr)
     }
 
@@ -454,6 +1105,21 @@ impl<'a> Parser<'a> {
             }));
         }
 
+        if self.match_token(Interpolation) {
+            return self.interpolation();
+        }
+
+        if self.match_token(Fun) {
+            let name = self.anonymous_name();
+            self.consume(LeftParen, "Expect '(' after 'fun'.")?;
+            let function = self.function_body(name, "function")?;
+            return Ok(Expr::Lambda(Lambda { function }));
+        }
+
+        if self.check(LeftParen) && self.is_arrow_function() {
+            return self.arrow_function();
+        }
+
         if self.match_token(LeftParen) {
             let group = Expr::Grouping(Grouping {
                 expression: Box::new(self.expression()?),
@@ -466,6 +1132,44 @@ impl<'a> Parser<'a> {
             return Ok(group);
         }
 
+        if self.match_token(LeftBracket) {
+            let bracket = self.previous().clone();
+            let mut elements = vec![];
+            if !self.check(RightBracket) {
+                loop {
+                    elements.push(self.expression()?);
+                    if !self.match_token(Comma) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(RightBracket, "Expect ']' after list elements.")?;
+
+            return Ok(Expr::List(List { bracket, elements }));
+        }
+
+        // A '{' only reaches here in expression position, since statements
+        // starting with one are parsed as blocks.
+        if self.match_token(LeftBrace) {
+            let brace = self.previous().clone();
+            let mut entries = vec![];
+            if !self.check(RightBrace) {
+                loop {
+                    let key = self.expression()?;
+                    self.consume(Colon, "Expect ':' after map key.")?;
+                    entries.push((key, self.expression()?));
+                    if !self.match_token(Comma) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(RightBrace, "Expect '}' after map entries.")?;
+
+            return Ok(Expr::Map(Map { brace, entries }));
+        }
+
         if self.match_token(Identifier) {
             return Ok(Expr::Variable(Variable {
                 token: self.previous().clone(),
@@ -477,7 +1181,6 @@ impl<'a> Parser<'a> {
 
     // helpers
     fn match_token(&mut self, ty: TokenType) -> bool {
-        //println!("match {} with {}", ty.name(), self.peek().ty.name());
         if self.check(ty) {
             self.advance();
             return true;
@@ -494,6 +1197,18 @@ impl<'a> Parser<'a> {
         self.peek().ty == ty
     }
 
+    fn check_next(&self, ty: TokenType) -> bool {
+        self.check_at(1, ty)
+    }
+
+    // whether the token `offset` places past the current one has type `ty`
+    fn check_at(&self, offset: usize, ty: TokenType) -> bool {
+        match self.tokens.get(self.current + offset) {
+            Some(token) => token.ty == ty,
+            None => false,
+        }
+    }
+
     fn peek(&self) -> &Token {
         &self.tokens[self.current]
     }
@@ -525,7 +1240,7 @@ impl<'a> Parser<'a> {
             return Err(self.error(message));
         }
 
-        return Ok(self.previous());
+        Ok(self.previous())
     }
 
     fn synchronize(&mut self) {
@@ -535,7 +1250,8 @@ impl<'a> Parser<'a> {
             }
 
             match self.peek().ty {
-                Class | For | Fun | Var | If | While | Print | Return => return,
+                Class | For | Fun | Var | Let | Const | If | While | Print | Return | Import
+                | From => return,
                 _ => {}
             }
 
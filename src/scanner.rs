@@ -1,9 +1,11 @@
 use crate::{
+    bigint::BigInt,
     error::LoxError,
+    interner::Interner,
     literal::Literal,
     token::{Token, TokenType},
 };
-use std::str::Chars;
+use std::{mem, num::IntErrorKind, rc::Rc, str::Chars};
 
 pub struct Scanner<'a> {
     source: &'a String,
@@ -11,6 +13,11 @@ pub struct Scanner<'a> {
     line: usize,
     errors: Vec<LoxError>,
     start: usize,
+    interner: Interner,
+    // value of the string or number literal just scanned, which can differ from its lexeme
+    literal: Literal,
+    // for each `${` still open, the braces opened inside it since
+    interpolations: Vec<usize>,
 }
 
 pub struct ScannerOutput {
@@ -26,16 +33,25 @@ impl<'a> Scanner<'a> {
             line: 1,
             errors: vec![],
             start: 0,
+            interner: Interner::new(),
+            literal: Literal::Nil,
+            interpolations: vec![],
         }
     }
 
-    pub fn run(self: &mut Self) -> ScannerOutput {
+    pub fn run(&mut self) -> ScannerOutput {
         let mut tokens: Vec<Token> = vec![];
         loop {
             self.start = self.offset();
             let ty = self.read_next_token();
 
             if ty == TokenType::EOF {
+                if !self.interpolations.is_empty() {
+                    self.errors.push(LoxError::Scanner {
+                        line: self.line,
+                        message: "Unterminated string interpolation.".into(),
+                    });
+                }
                 tokens.push(Token::new(ty, None, None, Some(self.line)));
                 break;
             }
@@ -43,20 +59,20 @@ impl<'a> Scanner<'a> {
             tokens.push(self.create_token(ty));
         }
 
-        let output = ScannerOutput {
+        ScannerOutput {
             tokens,
             errors: self.errors.clone(),
-        };
-
-        output
+        }
     }
 
     fn create_token(&mut self, ty: TokenType) -> Token {
-        let raw = self.source[self.start..self.offset()].to_string();
-        let lexeme = raw.clone();
+        let source = self.source;
+        let raw = &source[self.start..self.offset()];
+        let lexeme = self.interner.intern(raw);
         let literal = match ty {
-            TokenType::StringLit => Literal::String(raw[1..raw.len() - 1].to_string()),
-            TokenType::NumberLit => Literal::Number(raw.parse().unwrap()),
+            TokenType::StringLit | TokenType::Interpolation | TokenType::NumberLit => {
+                mem::replace(&mut self.literal, Literal::Nil)
+            }
             TokenType::False | TokenType::True => Literal::Boolean(raw.parse().unwrap()),
             _ => Literal::Nil,
         };
@@ -64,19 +80,99 @@ impl<'a> Scanner<'a> {
         Token::new(ty, Some(literal), Some(lexeme), Some(self.line))
     }
 
-    fn read_next_token(self: &mut Self) -> TokenType {
+    fn read_next_token(&mut self) -> TokenType {
         while let Some(char) = self.chars.next() {
             match char {
                 '(' => return TokenType::LeftParen,
                 ')' => return TokenType::RightParen,
-                '{' => return TokenType::LeftBrace,
-                '}' => return TokenType::RightBrace,
-                '-' => return TokenType::Minus,
-                '+' => return TokenType::Plus,
+                '{' => {
+                    if let Some(depth) = self.interpolations.last_mut() {
+                        *depth += 1;
+                    }
+                    return TokenType::LeftBrace;
+                }
+                '}' => match self.interpolations.last_mut() {
+                    // closes an interpolation, so the rest of its string follows
+                    Some(0) => {
+                        self.interpolations.pop();
+                        if let Some(ty) = self.match_string() {
+                            return ty;
+                        }
+                    }
+                    Some(depth) => {
+                        *depth -= 1;
+                        return TokenType::RightBrace;
+                    }
+                    None => return TokenType::RightBrace,
+                },
+                '[' => return TokenType::LeftBracket,
+                ']' => return TokenType::RightBracket,
+                '-' => {
+                    if self.match_next('-') {
+                        return TokenType::MinusMinus;
+                    }
+                    if self.match_next('=') {
+                        return TokenType::MinusEqual;
+                    }
+                    return TokenType::Minus;
+                }
+                '+' => {
+                    if self.match_next('+') {
+                        return TokenType::PlusPlus;
+                    }
+                    if self.match_next('=') {
+                        return TokenType::PlusEqual;
+                    }
+                    return TokenType::Plus;
+                }
                 ',' => return TokenType::Comma,
-                '.' => return TokenType::Dot,
+                ':' => return TokenType::Colon,
+                '?' => {
+                    if self.match_next('?') {
+                        return TokenType::QuestionQuestion;
+                    }
+                    if self.match_next('.') {
+                        return TokenType::QuestionDot;
+                    }
+                    return TokenType::Question;
+                }
+                '.' => {
+                    if self.match_next('.') {
+                        if self.match_next('.') {
+                            return TokenType::DotDotDot;
+                        }
+                        if self.match_next('=') {
+                            return TokenType::DotDotEqual;
+                        }
+                        return TokenType::DotDot;
+                    }
+                    return TokenType::Dot;
+                }
                 ';' => return TokenType::SemiColon,
-                '*' => return TokenType::Star,
+                '*' => {
+                    if self.match_next('*') {
+                        return TokenType::StarStar;
+                    }
+                    if self.match_next('=') {
+                        return TokenType::StarEqual;
+                    }
+                    return TokenType::Star;
+                }
+                '%' => {
+                    if self.match_next('=') {
+                        return TokenType::PercentEqual;
+                    }
+                    return TokenType::Percent;
+                }
+                '~' => {
+                    if self.match_next('/') {
+                        return TokenType::TildeSlash;
+                    }
+                    return TokenType::Tilde;
+                }
+                '&' => return TokenType::Ampersand,
+                '|' => return TokenType::Pipe,
+                '^' => return TokenType::Caret,
                 '!' => {
                     if self.match_next('=') {
                         return TokenType::BangEqual;
@@ -87,15 +183,24 @@ impl<'a> Scanner<'a> {
                     if self.match_next('=') {
                         return TokenType::EqualEqual;
                     }
+                    if self.match_next('>') {
+                        return TokenType::Arrow;
+                    }
                     return TokenType::Equal;
                 }
                 '>' => {
+                    if self.match_next('>') {
+                        return TokenType::GreaterGreater;
+                    }
                     if self.match_next('=') {
                         return TokenType::GreaterEqual;
                     };
                     return TokenType::Greater;
                 }
                 '<' => {
+                    if self.match_next('<') {
+                        return TokenType::LessLess;
+                    }
                     if self.match_next('=') {
                         return TokenType::LessEqual;
                     };
@@ -104,6 +209,8 @@ impl<'a> Scanner<'a> {
                 '/' => {
                     if self.match_next('/') {
                         self.ignore_line();
+                    } else if self.match_next('=') {
+                        return TokenType::SlashEqual;
                     } else {
                         return TokenType::Slash;
                     }
@@ -123,6 +230,8 @@ impl<'a> Scanner<'a> {
                         if let Some(ty) = self.match_number() {
                             return ty;
                         }
+                        // skip the malformed number rather than folding it into the next token
+                        self.start = self.offset();
                     } else if char.is_ascii_alphabetic() || char == '_' {
                         if let Some(ty) = self.match_identifier() {
                             return ty;
@@ -138,63 +247,154 @@ impl<'a> Scanner<'a> {
             }
         }
 
-        return TokenType::EOF;
+        TokenType::EOF
     }
 
     // handlers
     fn match_string(&mut self) -> Option<TokenType> {
         let mut literal: String = String::new();
 
-        while let Some(next) = self.peek() {
-            if next == '"' {
-                break;
+        let ty = loop {
+            match self.chars.next() {
+                Some('"') => break TokenType::StringLit,
+                Some('\\') => {
+                    if let Some(escaped) = self.match_escape() {
+                        literal.push(escaped);
+                    }
+                }
+                Some('$') if self.match_next('{') => {
+                    self.interpolations.push(0);
+                    break TokenType::Interpolation;
+                }
+                Some(next) => {
+                    if next == '\n' {
+                        self.line += 1
+                    }
+                    literal.push(next);
+                }
+                None => {
+                    self.errors.push(LoxError::Scanner {
+                        line: self.line,
+                        message: "Unterminated string.".into(),
+                    });
+                    return None;
+                }
             }
+        };
 
-            self.chars.next();
+        self.literal = Literal::String(self.interner.intern(&literal));
+        Some(ty)
+    }
 
-            if next == '\n' {
-                self.line += 1
+    // the character an escape sequence stands for, after its backslash
+    fn match_escape(&mut self) -> Option<char> {
+        let escaped = match self.peek()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '"' => '"',
+            '\\' => '\\',
+            '$' => '$',
+            'u' => {
+                self.chars.next();
+                return self.match_unicode_escape();
             }
+            other => {
+                self.errors.push(LoxError::Scanner {
+                    line: self.line,
+                    message: format!("Invalid escape sequence '\\{}'.", other),
+                });
+                return None;
+            }
+        };
+        self.chars.next();
+        Some(escaped)
+    }
 
-            literal.push(next);
+    // `\u{...}` with one to six hex digits naming a unicode scalar value
+    fn match_unicode_escape(&mut self) -> Option<char> {
+        let mut digits = String::new();
+        if self.match_next('{') {
+            while let Some(next) = self.peek().filter(char::is_ascii_hexdigit) {
+                self.chars.next();
+                digits.push(next);
+            }
         }
 
-        if self.offset() == self.source.len() {
+        let escaped = match self.match_next('}') && (1..=6).contains(&digits.len()) {
+            true => u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32),
+            false => None,
+        };
+        if escaped.is_none() {
             self.errors.push(LoxError::Scanner {
                 line: self.line,
-                message: "Unterminated string.".into(),
+                message: "Invalid unicode escape sequence.".into(),
             });
-            return None;
         }
-
-        self.chars.next();
-
-        return Some(TokenType::StringLit);
+        escaped
     }
 
     fn match_number(&mut self) -> Option<TokenType> {
-        while let Some(next) = self.peek() {
-            if next.is_ascii_digit() {
+        let radix = match (&self.source[self.start..self.offset()], self.peek()) {
+            ("0", Some('x' | 'X')) => 16,
+            ("0", Some('o' | 'O')) => 8,
+            ("0", Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+
+        if radix == 10 {
+            self.skip_digits();
+            if self.peek() == Some('.')
+                && self.peek_next().is_some_and(|next| next.is_ascii_digit())
+            {
                 self.chars.next();
-                continue;
+                self.skip_digits();
             }
-            break;
+            if matches!(self.peek(), Some('e' | 'E')) {
+                self.chars.next();
+                if matches!(self.peek(), Some('+' | '-')) {
+                    self.chars.next();
+                }
+                self.skip_digits();
+            }
+        } else {
+            self.chars.next();
         }
 
-        if self.peek().is_some_and(|next| next == '.')
-            && self.peek_next().is_some_and(|next| next.is_ascii_digit())
+        // anything word-like running on from the number makes it malformed
+        while self
+            .peek()
+            .is_some_and(|next| next.is_ascii_alphanumeric() || next == '_')
         {
             self.chars.next();
-            while let Some(next) = self.peek() {
-                if next.is_ascii_digit() {
-                    self.chars.next();
-                    continue;
-                }
-                break;
+        }
+
+        let raw = &self.source[self.start..self.offset()];
+        match parse_number(raw, radix) {
+            Ok(literal) => {
+                self.literal = literal;
+                Some(TokenType::NumberLit)
+            }
+            Err(message) => {
+                self.errors.push(LoxError::Scanner {
+                    line: self.line,
+                    message,
+                });
+                None
             }
         }
+    }
 
-        return Some(TokenType::NumberLit);
+    fn skip_digits(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|next| next.is_ascii_digit() || next == '_')
+        {
+            self.chars.next();
+        }
     }
 
     fn match_identifier(&mut self) -> Option<TokenType> {
@@ -210,7 +410,7 @@ impl<'a> Scanner<'a> {
         if let Some(ty) = TokenType::get_keyword_token_type(lexeme) {
             return Some(ty);
         }
-        return Some(TokenType::Identifier);
+        Some(TokenType::Identifier)
     }
 
     // helpers
@@ -220,9 +420,7 @@ impl<'a> Scanner<'a> {
 
     fn peek_next(&mut self) -> Option<char> {
         let mut copy_chars = self.chars.clone();
-        if copy_chars.next().is_none() {
-            return None;
-        }
+        copy_chars.next()?;
         copy_chars.next()
     }
 
@@ -237,7 +435,7 @@ impl<'a> Scanner<'a> {
     }
 
     fn offset(&mut self) -> usize {
-        return self.source.len() - self.chars.as_str().len();
+        self.source.len() - self.chars.as_str().len()
     }
 
     fn ignore_line(&mut self) {
@@ -250,3 +448,42 @@ impl<'a> Scanner<'a> {
         }
     }
 }
+
+// The value of a number literal: an integer unless it has a fraction or an
+// exponent, and a bigint if it has the suffix `n` or doesn't fit in an i64.
+// Digit separators are only allowed between two digits.
+fn parse_number(raw: &str, radix: u32) -> Result<Literal, String> {
+    let invalid = || format!("Invalid number literal '{}'.", raw);
+    let (digits, big) = match raw.strip_suffix('n') {
+        Some(digits) => (digits, true),
+        None => (raw, false),
+    };
+    let chars: Vec<char> = digits.chars().collect();
+    for (index, char) in chars.iter().enumerate() {
+        let separates_digits = index > 0
+            && chars[index - 1].is_digit(radix)
+            && chars
+                .get(index + 1)
+                .is_some_and(|next| next.is_digit(radix));
+        if *char == '_' && !separates_digits {
+            return Err(invalid());
+        }
+    }
+
+    let digits = digits.replace('_', "");
+    if radix == 10 && digits.contains(['.', 'e', 'E']) {
+        return match big {
+            true => Err(invalid()),
+            false => digits.parse().map(Literal::Number).map_err(|_| invalid()),
+        };
+    }
+
+    let digits = if radix == 10 { &digits } else { &digits[2..] };
+    match i64::from_str_radix(digits, radix) {
+        Ok(integer) if !big => Ok(Literal::Integer(integer)),
+        Err(error) if *error.kind() != IntErrorKind::PosOverflow => Err(invalid()),
+        _ => BigInt::parse(digits, radix)
+            .map(|big| Literal::BigInt(Rc::new(big)))
+            .ok_or_else(invalid),
+    }
+}
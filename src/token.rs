@@ -1,11 +1,14 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    rc::Rc,
+};
 
 use crate::literal::Literal;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub ty: TokenType,
-    pub lexeme: String,
+    pub lexeme: Rc<str>,
     pub literal: Literal,
     pub line: usize,
 }
@@ -14,12 +17,12 @@ impl Token {
     pub fn new(
         ty: TokenType,
         literal: Option<Literal>,
-        lexeme: Option<String>,
+        lexeme: Option<Rc<str>>,
         line: Option<usize>,
     ) -> Self {
         Self {
             ty,
-            lexeme: lexeme.unwrap_or(String::new()),
+            lexeme: lexeme.unwrap_or_else(|| Rc::from("")),
             literal: literal.unwrap_or(Literal::Nil),
             line: line.unwrap_or(1),
         }
@@ -45,47 +48,89 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
+    Question,
     Dot,
+    DotDot,
+    DotDotEqual,
+    DotDotDot,
     Minus,
     Plus,
     SemiColon,
     Slash,
     Star,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
 
     // One or two character tokens.
     Bang,
     BangEqual,
     Equal,
     EqualEqual,
+    Arrow,
     Greater,
     GreaterEqual,
     Less,
     LessEqual,
+    StarStar,
+    LessLess,
+    GreaterGreater,
+    QuestionQuestion,
+    QuestionDot,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    PlusPlus,
+    MinusMinus,
+    // integer division, since `//` starts a comment
+    TildeSlash,
 
     // Literals.
     Identifier,
     StringLit,
+    // the part of a string literal before a `${` interpolation
+    Interpolation,
     NumberLit,
 
     // Keywords.
     And,
+    As,
+    Break,
+    Catch,
     Class,
+    Const,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
+    From,
     If,
+    Import,
+    In,
+    Let,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
+    #[allow(clippy::upper_case_acronyms)]
     EOF,
 }
 
@@ -96,38 +141,77 @@ impl TokenType {
             TokenType::RightParen => String::from("RIGHT_PAREN"),
             TokenType::LeftBrace => String::from("LEFT_BRACE"),
             TokenType::RightBrace => String::from("RIGHT_BRACE"),
+            TokenType::LeftBracket => String::from("LEFT_BRACKET"),
+            TokenType::RightBracket => String::from("RIGHT_BRACKET"),
             TokenType::Comma => String::from("COMMA"),
+            TokenType::Colon => String::from("COLON"),
+            TokenType::Question => String::from("QUESTION"),
             TokenType::Dot => String::from("DOT"),
+            TokenType::DotDot => String::from("DOT_DOT"),
+            TokenType::DotDotEqual => String::from("DOT_DOT_EQUAL"),
+            TokenType::DotDotDot => String::from("DOT_DOT_DOT"),
             TokenType::Minus => String::from("MINUS"),
             TokenType::Plus => String::from("PLUS"),
             TokenType::SemiColon => String::from("SEMICOLON"),
             TokenType::Slash => String::from("SLASH"),
             TokenType::Star => String::from("STAR"),
+            TokenType::Percent => String::from("PERCENT"),
+            TokenType::Ampersand => String::from("AMPERSAND"),
+            TokenType::Pipe => String::from("PIPE"),
+            TokenType::Caret => String::from("CARET"),
+            TokenType::Tilde => String::from("TILDE"),
             TokenType::Bang => String::from("BANG"),
             TokenType::BangEqual => String::from("BANG_EQUAL"),
             TokenType::Equal => String::from("EQUAL"),
             TokenType::EqualEqual => String::from("EQUAL_EQUAL"),
+            TokenType::Arrow => String::from("ARROW"),
             TokenType::Greater => String::from("GREATER"),
             TokenType::GreaterEqual => String::from("GREATER_EQUAL"),
             TokenType::Less => String::from("LESS"),
             TokenType::LessEqual => String::from("LESS_EQUAL"),
+            TokenType::StarStar => String::from("STAR_STAR"),
+            TokenType::LessLess => String::from("LESS_LESS"),
+            TokenType::GreaterGreater => String::from("GREATER_GREATER"),
+            TokenType::QuestionQuestion => String::from("QUESTION_QUESTION"),
+            TokenType::QuestionDot => String::from("QUESTION_DOT"),
+            TokenType::PlusEqual => String::from("PLUS_EQUAL"),
+            TokenType::MinusEqual => String::from("MINUS_EQUAL"),
+            TokenType::StarEqual => String::from("STAR_EQUAL"),
+            TokenType::SlashEqual => String::from("SLASH_EQUAL"),
+            TokenType::PercentEqual => String::from("PERCENT_EQUAL"),
+            TokenType::PlusPlus => String::from("PLUS_PLUS"),
+            TokenType::MinusMinus => String::from("MINUS_MINUS"),
+            TokenType::TildeSlash => String::from("TILDE_SLASH"),
             TokenType::Identifier => String::from("IDENTIFIER"),
             TokenType::StringLit => String::from("STRING"),
+            TokenType::Interpolation => String::from("INTERPOLATION"),
             TokenType::NumberLit => String::from("NUMBER"),
             TokenType::And => String::from("AND"),
+            TokenType::As => String::from("AS"),
+            TokenType::Break => String::from("BREAK"),
+            TokenType::Catch => String::from("CATCH"),
             TokenType::Class => String::from("CLASS"),
+            TokenType::Const => String::from("CONST"),
+            TokenType::Continue => String::from("CONTINUE"),
             TokenType::Else => String::from("ELSE"),
             TokenType::False => String::from("FALSE"),
+            TokenType::Finally => String::from("FINALLY"),
             TokenType::Fun => String::from("FUN"),
             TokenType::For => String::from("FOR"),
+            TokenType::From => String::from("FROM"),
             TokenType::If => String::from("IF"),
+            TokenType::Import => String::from("IMPORT"),
+            TokenType::In => String::from("IN"),
+            TokenType::Let => String::from("LET"),
             TokenType::Nil => String::from("NIL"),
             TokenType::Or => String::from("OR"),
             TokenType::Print => String::from("PRINT"),
             TokenType::Return => String::from("RETURN"),
             TokenType::Super => String::from("SUPER"),
             TokenType::This => String::from("THIS"),
+            TokenType::Throw => String::from("THROW"),
             TokenType::True => String::from("TRUE"),
+            TokenType::Try => String::from("TRY"),
             TokenType::Var => String::from("VAR"),
             TokenType::While => String::from("WHILE"),
             TokenType::EOF => String::from("EOF"),
@@ -137,19 +221,31 @@ impl TokenType {
     pub fn get_keyword_token_type(lexeme: String) -> Option<TokenType> {
         match lexeme.as_str() {
             "and" => Some(TokenType::And),
+            "as" => Some(TokenType::As),
+            "break" => Some(TokenType::Break),
+            "catch" => Some(TokenType::Catch),
             "class" => Some(TokenType::Class),
+            "const" => Some(TokenType::Const),
+            "continue" => Some(TokenType::Continue),
             "else" => Some(TokenType::Else),
             "false" => Some(TokenType::False),
+            "finally" => Some(TokenType::Finally),
             "fun" => Some(TokenType::Fun),
             "for" => Some(TokenType::For),
+            "from" => Some(TokenType::From),
             "if" => Some(TokenType::If),
+            "import" => Some(TokenType::Import),
+            "in" => Some(TokenType::In),
+            "let" => Some(TokenType::Let),
             "nil" => Some(TokenType::Nil),
             "or" => Some(TokenType::Or),
             "print" => Some(TokenType::Print),
             "return" => Some(TokenType::Return),
             "super" => Some(TokenType::Super),
             "this" => Some(TokenType::This),
+            "throw" => Some(TokenType::Throw),
             "true" => Some(TokenType::True),
+            "try" => Some(TokenType::Try),
             "var" => Some(TokenType::Var),
             "while" => Some(TokenType::While),
             _ => None,
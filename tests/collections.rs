@@ -0,0 +1,112 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn lists_are_indexed_updated_and_shared() {
+    let source = r#"
+        var l = [1, 2, 3];
+        l[1] = "b";
+        print l;
+        print pop(l);
+        insert(l, 0, 0);
+        print remove(l, 1);
+        print l;
+        print len(l) + len("héllo");
+        var alias = l;
+        push(alias, 4);
+        print l;
+        print l == alias;
+        print l == [0, "b", 4];
+        print slice(l, 1, 3);
+    "#;
+    assert_eq!(
+        output(source),
+        "[1, \"b\", 3]\n3\n1\n[0, \"b\"]\n7\n[0, \"b\", 4]\ntrue\nfalse\n[\"b\", 4]\n"
+    );
+}
+
+#[test]
+fn bad_indexes_are_runtime_errors_at_the_bracket() {
+    let stderr = error("var l = [1, 2];\nprint l[2];", 70);
+    assert_eq!(stderr, "[line 2] Error at ]: List index out of range.\n");
+    let stderr = error("print [1][0.5];", 70);
+    assert_eq!(
+        stderr,
+        "[line 1] Error at ]: List index must be an integer.\n"
+    );
+    let stderr = error("print 1[0];", 70);
+    assert_eq!(
+        stderr,
+        "[line 1] Error at ]: Only lists and maps can be indexed.\n"
+    );
+}
+
+#[test]
+fn strings_inside_lists_print_quoted() {
+    assert_eq!(output("print [\"1\", 1];\nprint \"1\";"), "[\"1\", 1]\n1\n");
+}
+
+#[test]
+fn self_containing_lists_print_without_overflowing() {
+    let source = r#"
+        var l = [1, 2];
+        push(l, l);
+        print l;
+        var shared = [0];
+        print [shared, shared];
+    "#;
+    assert_eq!(output(source), "[1, 2, [...]]\n[[0], [0]]\n");
+}
+
+#[test]
+fn maps_keep_insertion_order_and_normalize_number_keys() {
+    let source = r#"
+        { print "still a block"; }
+        var m = {"1": 1, 1: "one", true: nil, nil: [2, "x"]};
+        print m;
+        print m[1.0];
+        m["1"] = "first";
+        m["nested"] = {};
+        m["nested"]["key"] = 0;
+        print keys(m);
+        print values(m);
+        print len(m);
+        print has(m, "nested");
+        delete(m, "nested");
+        print has(m, "nested");
+    "#;
+    assert_eq!(
+        output(source),
+        "still a block\n{\"1\": 1, 1: \"one\", true: nil, nil: [2, \"x\"]}\none\n\
+         [\"1\", 1, true, nil, \"nested\"]\n[\"first\", \"one\", nil, [2, \"x\"], {\"key\": 0}]\n\
+         5\ntrue\nfalse\n"
+    );
+}
+
+#[test]
+fn bad_map_keys_are_runtime_errors() {
+    let stderr = error("var m = {};\nprint m[\"missing\"];", 70);
+    assert_eq!(stderr, "[line 2] Error at ]: Undefined key 'missing'.\n");
+    let stderr = error("var m = {};\nm[[1]] = 2;", 70);
+    assert_eq!(
+        stderr,
+        "[line 2] Error at ]: Map keys must be strings, numbers, booleans or nil.\n"
+    );
+}
+
+#[test]
+fn self_containing_maps_print_without_overflowing() {
+    let source = r#"
+        var m = {"a": 1};
+        m["self"] = m;
+        print m;
+        var l = [m];
+        m["list"] = l;
+        print l;
+    "#;
+    assert_eq!(
+        output(source),
+        "{\"a\": 1, \"self\": {...}}\n[{\"a\": 1, \"self\": {...}, \"list\": [...]}]\n"
+    );
+}
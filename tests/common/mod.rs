@@ -0,0 +1,71 @@
+#![allow(dead_code)]
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+static NEXT_DIRECTORY: AtomicUsize = AtomicUsize::new(0);
+
+// A fresh directory for one test's scripts.
+fn directory() -> PathBuf {
+    let index = NEXT_DIRECTORY.fetch_add(1, Ordering::Relaxed);
+    let directory = env::temp_dir().join(format!("lox-test-{}-{}", std::process::id(), index));
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+// Writes `files` into a fresh directory and runs the first one with `args`,
+// in which `{dir}` stands for that directory.
+pub fn run_files(files: &[(&str, &str)], args: &[&str]) -> Output {
+    let directory = directory();
+    for (name, source) in files {
+        let path = directory.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+
+    let root = directory.display().to_string();
+    let output = Command::new(env!("CARGO_BIN_EXE_lox-interpreter"))
+        .args(args.iter().map(|arg| arg.replace("{dir}", &root)))
+        .arg(directory.join(files[0].0))
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    Output {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        code: output.status.code(),
+    }
+}
+
+pub fn run_with(source: &str, args: &[&str]) -> Output {
+    run_files(&[("main.lox", source)], args)
+}
+
+pub fn run(source: &str) -> Output {
+    run_with(source, &[])
+}
+
+// Runs a script that must succeed, returning what it printed.
+pub fn output(source: &str) -> String {
+    let output = run(source);
+    assert_eq!(output.code, Some(0), "stderr: {}", output.stderr);
+    output.stdout
+}
+
+// Runs a script that must fail with `code`, returning its error output.
+pub fn error(source: &str, code: i32) -> String {
+    let output = run(source);
+    assert_eq!(output.code, Some(code), "stdout: {}", output.stdout);
+    output.stderr
+}
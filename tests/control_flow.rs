@@ -0,0 +1,88 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn break_and_continue_only_leave_the_innermost_loop() {
+    let source = r#"
+        for (var i = 0; i < 3; i = i + 1) {
+            if (i == 1) continue;
+            var j = 0;
+            while (true) { j = j + 1; if (j == 2) break; }
+            print [i, j];
+        }
+    "#;
+    assert_eq!(output(source), "[0, 2]\n[2, 2]\n");
+}
+
+#[test]
+fn continue_in_a_for_loop_still_runs_the_increment() {
+    let source = r#"
+        var total = 0;
+        for (var i = 0; i < 5; i = i + 1) {
+            if (i == 2) continue;
+            total = total + i;
+        }
+        print total;
+    "#;
+    assert_eq!(output(source), "8\n");
+}
+
+#[test]
+fn break_and_continue_outside_a_loop_are_static_errors() {
+    assert!(error("break;", 65).contains("Can't use 'break' outside of a loop."));
+    assert!(error("print \"never\";\ncontinue;", 65).contains("outside of a loop"));
+    let nested = "while (true) { fun f() { break; } f(); }";
+    assert!(error(nested, 65).contains("Can't use 'break' outside of a loop."));
+}
+
+#[test]
+fn parameters_are_not_read_in_their_own_initializer() {
+    let source = r#"
+        fun add(a, b) { { var c = a + b; print c; } }
+        add(1, 2);
+    "#;
+    assert_eq!(output(source), "3\n");
+}
+
+#[test]
+fn for_in_loops_over_every_iterable() {
+    let source = r#"
+        for (var c in "hé!") print c;
+        for (var x in [1, 2]) print x;
+        for (var k in {"a": 1, "b": 2}) print k;
+        for (var i in 0..3) print i;
+        for (var i in 3..=3) print i;
+
+        var countdown = {
+            "iter": () => {
+                var n = 2;
+                return {"next": () => { n -= 1; return n >= 0 ? n : nil; }};
+            }
+        };
+        for (var n in countdown) print n;
+    "#;
+    assert_eq!(output(source), "h\né\n!\n1\n2\na\nb\n0\n1\n2\n3\n1\n0\n");
+}
+
+#[test]
+fn for_in_declares_a_fresh_binding_per_item() {
+    let source = r#"
+        var fs = [];
+        for (let i in 0..3) push(fs, () => i);
+        print [fs[0](), fs[1](), fs[2]()];
+        for (const x in [1, 2, 3]) {
+            if (x == 1) continue;
+            if (x == 3) break;
+            print x;
+        }
+    "#;
+    assert_eq!(output(source), "[0, 1, 2]\n2\n");
+
+    let stderr = error("for (const x in [1]) x = 2;", 65);
+    assert!(
+        stderr.contains("Cannot assign to constant 'x'."),
+        "{}",
+        stderr
+    );
+}
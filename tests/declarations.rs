@@ -0,0 +1,180 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn let_bindings_are_block_scoped_with_a_dead_zone() {
+    let source = r#"
+        let x = "outer";
+        { let x = "inner"; print x; }
+        print x;
+    "#;
+    assert_eq!(output(source), "inner\nouter\n");
+
+    let stderr = error("{\n  print x;\n  let x = 1;\n}", 65);
+    assert!(stderr.starts_with("[line 2] Error at x:"), "{}", stderr);
+    // a use from a function declared earlier is only caught once it runs
+    let stderr = error("fun f() { return x; }\nprint f();\nlet x = 1;", 70);
+    assert!(
+        stderr.contains("Cannot access 'x' before initialization."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn let_bindings_can_not_be_redeclared_in_their_scope() {
+    for source in [
+        "let a = 1;\nlet a = 2;",
+        "let a = 1;\nvar a = 2;",
+        "var a = 1;\nlet a = 2;",
+        "let a = 1;\nfun a() {}",
+        "let a = 1;\na = 5;\nvar a = 2;",
+    ] {
+        let stderr = error(source, 70);
+        assert!(
+            stderr.contains("Already a variable named 'a' in this scope."),
+            "{}",
+            stderr
+        );
+    }
+    let stderr = error("{\n  let a = 1;\n  fun a() {}\n}", 65);
+    assert!(
+        stderr.contains("[line 3] Error at a: Already a variable named 'a' in this scope."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn for_loops_declare_a_fresh_let_binding_per_iteration() {
+    let source = r#"
+        var fs = [];
+        for (let i = 0; i < 3; i++) push(fs, () => i);
+        print [fs[0](), fs[1](), fs[2]()];
+        for (const limit = 2; limit > 0;) {
+            print limit;
+            break;
+        }
+    "#;
+    assert_eq!(output(source), "[0, 1, 2]\n2\n");
+
+    let stderr = error("for (let i = 0; i < 1; i++) {}\nprint i;", 70);
+    assert!(stderr.contains("Undefined variable 'i'."), "{}", stderr);
+    let stderr = error("for (const i = 0; i < 3; i++) {}", 65);
+    assert!(
+        stderr.contains("Cannot assign to constant 'i'."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn constants_can_not_be_assigned() {
+    assert_eq!(output("const a = 1;\nprint a;"), "1\n");
+    for source in [
+        "const a = 1;\na = 2;",
+        "const a = 1;\na += 1;",
+        "const a = 1;\na++;",
+    ] {
+        // globals are only checked when the assignment runs
+        let stderr = error(source, 70);
+        assert!(
+            stderr.contains("Cannot assign to constant 'a'."),
+            "{}",
+            stderr
+        );
+    }
+    let stderr = error("{\n  const a = 1;\n  a = 2;\n}", 65);
+    assert!(
+        stderr.contains("Cannot assign to constant 'a'."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn constants_can_not_be_redeclared_in_their_scope() {
+    for source in [
+        "const X = 1;\nvar X = 2;\nX = 3;\nprint X;",
+        "const X = 1;\nconst X = 2;",
+        "const X = 1;\nfun X() {}",
+    ] {
+        let stderr = error(source, 70);
+        assert!(
+            stderr.contains("[line 2] Error at X: Already a constant named 'X' in this scope."),
+            "{}",
+            stderr
+        );
+    }
+    let stderr = error("{\n  const X = 1;\n  var X = 2;\n}", 65);
+    assert!(
+        stderr.contains("Already a constant named 'X' in this scope."),
+        "{}",
+        stderr
+    );
+
+    // an inner scope may still shadow it
+    assert_eq!(
+        output("const X = 1;\n{ var X = 2; print X; }\nprint X;"),
+        "2\n1\n"
+    );
+}
+
+#[test]
+fn assignment_keeps_a_variable_a_variable() {
+    assert_eq!(output("var a = 1;\na = 2;\nvar a = 3;\nprint a;"), "3\n");
+}
+
+#[test]
+fn destructuring_declarations_bind_nested_patterns_with_defaults() {
+    let source = r#"
+        var [a, [b, c], d = 4] = [1, [2, 3]];
+        print [a, b, c, d];
+        let {x, y: [p, q], z = "z"} = {"x": 1, "y": [2, 3]};
+        print [x, p, q, z];
+        const [first] = ["only"];
+        print first;
+    "#;
+    assert_eq!(output(source), "[1, 2, 3, 4]\n[1, 2, 3, \"z\"]\nonly\n");
+
+    let stderr = error("var [a] = 1;", 70);
+    assert!(stderr.contains("Only lists can be destructured with '[...]'."));
+}
+
+#[test]
+fn destructuring_declarations_bind_each_name_once() {
+    for source in [
+        "var [a, a] = [1, 2];",
+        "let {x: a, y: [a]} = {};",
+        "{\n  const [b, {c: a}, a] = [];\n}",
+    ] {
+        let stderr = error(source, 65);
+        assert!(
+            stderr.contains("Duplicate name 'a' in pattern."),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn destructuring_assignment_swaps_variables_and_elements() {
+    let source = r#"
+        var a = 1;
+        var b = 2;
+        [a, b] = [b, a];
+        print [a, b];
+        var xs = [1, 2, 3];
+        [xs[0], xs[2]] = [xs[2], xs[0]];
+        print xs;
+        var m = {};
+        [m["k"], a = "default"] = ["v", nil];
+        print m;
+        print a;
+    "#;
+    assert_eq!(
+        output(source),
+        "[2, 1]\n[3, 2, 1]\n{\"k\": \"v\"}\ndefault\n"
+    );
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn thrown_values_are_caught_and_finally_always_runs() {
+    let source = r#"
+        try { throw "boom"; } catch (e) { print "caught " + e; } finally { print "finally"; }
+
+        fun f() {
+            try { return "returned"; } finally { print "cleanup"; }
+        }
+        print f();
+
+        try {
+            try { throw 1; } finally { print "inner finally"; }
+        } catch (e) {
+            print e + 1;
+        }
+    "#;
+    assert_eq!(
+        output(source),
+        "caught boom\nfinally\ncleanup\nreturned\ninner finally\n2\n"
+    );
+}
+
+#[test]
+fn runtime_errors_are_caught_as_maps() {
+    let source = r#"
+        try {
+            print 1 + nil;
+        } catch (e) {
+            print e["message"];
+            print e["line"];
+        }
+    "#;
+    assert_eq!(
+        output(source),
+        "Operands must be two numbers or two strings.\n3\n"
+    );
+}
+
+#[test]
+fn uncaught_throws_are_reported_at_the_throw() {
+    let stderr = error("print 1;\nthrow \"uncaught\";", 70);
+    assert_eq!(stderr, "[line 2] Error at throw: uncaught\n");
+}
+
+#[test]
+fn limits_can_not_be_caught() {
+    let output = common::run_with(
+        "try { while (true) {} } catch (e) { print \"caught\"; }",
+        &["--max-steps=1000"],
+    );
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.stderr, "Error: Execution step limit exceeded.\n");
+}
+
+#[test]
+fn calls_returned_from_a_try_still_run_inside_it() {
+    let source = r#"
+        fun fail() { throw "failed"; }
+        fun attempt() {
+            try { return fail(); } catch (e) { return "caught " + e; } finally { print "finally"; }
+        }
+        print attempt();
+
+        while (true) {
+            try { break; } finally { print "left the loop"; }
+        }
+    "#;
+    assert_eq!(output(source), "finally\ncaught failed\nleft the loop\n");
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::{error, output, run_with};
+
+#[test]
+fn tail_calls_run_in_constant_stack() {
+    let source = r#"
+        fun count(n) { if (n == 0) return "done"; return count(n - 1); }
+        print count(200000);
+
+        fun even(n) { if (n == 0) return true; return odd(n - 1); }
+        fun odd(n) { if (n == 0) return false; return even(n - 1); }
+        print even(100001);
+    "#;
+    assert_eq!(output(source), "done\nfalse\n");
+}
+
+#[test]
+fn tail_calls_return_their_callee_result() {
+    let source = r#"
+        fun count(n) { if (n == 0) return "deep"; return count(n - 1); }
+        fun nested() { { return count(10); } }
+        print nested();
+
+        fun add(a, b) { return a + b; }
+        fun inc(x) { return add(x, 1); }
+        print inc(1) + inc(2);
+        fun now() { return clock(); }
+        print now() > 0;
+    "#;
+    assert_eq!(output(source), "deep\n5\ntrue\n");
+}
+
+#[test]
+fn deep_recursion_is_a_stack_overflow_error() {
+    let source = "fun f(n) { return 1 + f(n + 1); }\nf(0);";
+    let stderr = error(source, 70);
+    assert!(
+        stderr.starts_with("[line 1] Error at ): Stack overflow.\n[line 1] in f()\n"),
+        "{}",
+        stderr
+    );
+
+    // the host stack runs out before the deepest depth allowed, and is caught too
+    let output = run_with(source, &["--max-call-depth=16384"]);
+    assert_eq!(output.code, Some(70));
+    assert!(
+        output.stderr.contains("Stack overflow."),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn call_depth_is_configurable_within_what_the_stack_holds() {
+    let source = "fun f(n) { if (n == 0) return 0; return 1 + f(n - 1); }\nprint f(100);";
+    let shallow = run_with(source, &["--max-call-depth=50"]);
+    assert_eq!(shallow.code, Some(70));
+    assert!(shallow.stderr.contains("Stack overflow."));
+
+    let deep = run_with(source, &["--max-call-depth=200"]);
+    assert_eq!(deep.stdout, "100\n");
+
+    for depth in ["0", "100000"] {
+        let invalid = run_with(source, &[&format!("--max-call-depth={}", depth)]);
+        assert_eq!(invalid.code, Some(64));
+        assert!(invalid
+            .stderr
+            .starts_with(&format!("Invalid value '{}' for --max-call-depth", depth)));
+    }
+}
+
+#[test]
+fn arrow_functions_take_expression_and_block_bodies() {
+    let source = r#"
+        var add = (a, b) => a + b;
+        var block = (x) => {
+            var doubled = x * 2;
+            return doubled + 1;
+        };
+        var empty = () => {};
+        var map = () => ({"a": 1});
+        print add(1, 2);
+        print block(4);
+        print empty();
+        print map();
+        print fun (x) { return -x; }(3);
+    "#;
+    assert_eq!(output(source), "3\n9\nnil\n{\"a\": 1}\n-3\n");
+}
+
+#[test]
+fn anonymous_functions_close_over_their_scope() {
+    let source = r#"
+        fun counter() { var n = 0; return () => { n = n + 1; return n; }; }
+        var next = counter();
+        next();
+        print next();
+    "#;
+    assert_eq!(output(source), "2\n");
+}
+
+#[test]
+fn malformed_arrow_bodies_are_reported_without_hanging() {
+    let stderr = error("var f = () => { return 1 };\nprint f();", 65);
+    assert!(
+        stderr.contains("Expect ';' after return value."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn parameters_take_defaults_rest_and_named_arguments() {
+    let source = r#"
+        fun f(a, b = a * 10, ...rest) { return [a, b, rest]; }
+        print f(1);
+        print f(1, 2, 3, 4);
+        print f(b: 5, a: 1);
+    "#;
+    assert_eq!(output(source), "[1, 10, []]\n[1, 2, [3, 4]]\n[1, 5, []]\n");
+
+    let stderr = error("fun f(a) {}\nf(c: 1);", 70);
+    assert!(stderr.contains("Unknown parameter 'c'."), "{}", stderr);
+    let stderr = error("fun f(a) {}\nf(1, a: 2);", 70);
+    assert!(
+        stderr.contains("Got more than one value for parameter 'a'."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn parameter_names_must_be_distinct() {
+    for source in [
+        "fun f(a, a) {}",
+        "fun f(a, ...a) {}",
+        "var f = (a, b, a = 1) => a;",
+    ] {
+        let stderr = error(source, 65);
+        assert!(stderr.contains("Duplicate parameter 'a'."), "{}", stderr);
+    }
+}
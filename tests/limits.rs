@@ -0,0 +1,80 @@
+mod common;
+
+use common::run_with;
+
+#[test]
+fn step_limit_stops_endless_loops() {
+    let output = run_with("while (true) {}", &["--max-steps=10000"]);
+    assert_eq!(output.code, Some(70));
+    assert_eq!(output.stderr, "Error: Execution step limit exceeded.\n");
+
+    let output = run_with(
+        "var i = 0;\nwhile (i < 10) i = i + 1;\nprint i;",
+        &["--max-steps=10000"],
+    );
+    assert_eq!(output.stdout, "10\n", "{}", output.stderr);
+}
+
+#[test]
+fn timeout_stops_endless_loops() {
+    let output = run_with("while (true) {}", &["--timeout-ms=100"]);
+    assert_eq!(output.code, Some(70));
+    assert_eq!(output.stderr, "Error: Execution timed out.\n");
+}
+
+#[test]
+fn memory_limit_stops_growing_strings() {
+    let output = run_with(
+        "var s = \"x\";\nwhile (true) s = s + s;",
+        &["--max-memory=100000"],
+    );
+    assert_eq!(output.code, Some(70));
+    assert_eq!(output.stderr, "Error: Out of memory (100000 byte limit).\n");
+}
+
+#[test]
+fn memory_of_finished_blocks_is_released() {
+    let source = r#"
+        var s = "abc";
+        for (var i = 0; i < 1000; i = i + 1) { var t = s + s; }
+        print "done";
+    "#;
+    let output = run_with(source, &["--max-memory=5000", "--memory-stats"]);
+    assert_eq!(output.stdout, "done\n", "{}", output.stderr);
+    assert!(output.stderr.starts_with("memory: "), "{}", output.stderr);
+}
+
+#[test]
+fn memory_limit_counts_list_elements_and_map_entries() {
+    let list = r#"
+        var l = [];
+        for (var i = 0; i < 100000; i = i + 1) push(l, "element");
+    "#;
+    let map = r#"
+        var m = {};
+        for (var i = 0; i < 100000; i = i + 1) m[i] = "value";
+    "#;
+    for source in [list, map] {
+        let output = run_with(source, &["--max-memory=100000"]);
+        assert_eq!(output.code, Some(70));
+        assert_eq!(output.stderr, "Error: Out of memory (100000 byte limit).\n");
+    }
+}
+
+#[test]
+fn memory_of_dropped_lists_and_maps_is_released() {
+    let source = r#"
+        for (var i = 0; i < 2000; i = i + 1) {
+            var l = [];
+            var m = {};
+            for (var j = 0; j < 50; j = j + 1) { push(l, "value"); m[j] = "value"; }
+        }
+        var l = [1, "a long string to be removed"];
+        remove(l, 1);
+        var m = {"key": "a long string to be removed"};
+        delete(m, "key");
+        print "done";
+    "#;
+    let output = run_with(source, &["--max-memory=20000"]);
+    assert_eq!(output.stdout, "done\n", "{}", output.stderr);
+}
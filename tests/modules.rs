@@ -0,0 +1,107 @@
+mod common;
+
+use common::run_files;
+
+#[test]
+fn imports_expose_a_module_namespace_and_selected_names() {
+    let output = run_files(
+        &[
+            (
+                "main.lox",
+                r#"
+                import "lib/math.lox" as math;
+                from "lib/math.lox" import square;
+                print math.square(3);
+                print square(4);
+                print math.name;
+                "#,
+            ),
+            (
+                "lib/math.lox",
+                r#"
+                print "loading";
+                var name = "math";
+                fun square(x) { return x * x; }
+                "#,
+            ),
+        ],
+        &[],
+    );
+    // the module runs once however often it is imported
+    assert_eq!(output.stdout, "loading\n9\n16\nmath\n", "{}", output.stderr);
+}
+
+#[test]
+fn import_cycles_are_reported() {
+    let output = run_files(
+        &[
+            ("main.lox", "import \"a.lox\" as a;"),
+            ("a.lox", "import \"b.lox\" as b;"),
+            ("b.lox", "import \"a.lox\" as a;"),
+        ],
+        &[],
+    );
+    assert_eq!(output.code, Some(70));
+    assert!(output.stderr.contains("Import cycle:"), "{}", output.stderr);
+    assert!(output.stderr.contains("a.lox -> "), "{}", output.stderr);
+}
+
+#[test]
+fn errors_name_the_module_they_come_from() {
+    let output = run_files(
+        &[
+            ("main.lox", "import \"broken.lox\" as b;"),
+            ("broken.lox", "print undefined;"),
+        ],
+        &[],
+    );
+    assert_eq!(output.code, Some(70));
+    assert!(
+        output.stderr.contains("broken.lox: [line 1]"),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn tail_calls_in_a_module_do_not_leak_into_the_importer() {
+    let output = run_files(
+        &[
+            (
+                "main.lox",
+                r#"
+                fun load() { import "side.lox" as s; return "load result"; }
+                print load();
+                fun r(n) { if (n == 0) { import "deep.lox" as d; return 1; } return 1 + r(n - 1); }
+                print r(5);
+                "#,
+            ),
+            (
+                "side.lox",
+                "fun side() { return 99; }\nreturn side();",
+            ),
+            (
+                "deep.lox",
+                "fun count(n) { if (n == 0) return \"deep\"; return count(n - 1); }\nprint count(100000);",
+            ),
+        ],
+        &[],
+    );
+    assert_eq!(output.stdout, "load result\ndeep\n6\n", "{}", output.stderr);
+}
+
+#[test]
+fn imports_are_searched_along_the_module_path() {
+    let output = run_files(
+        &[
+            ("main.lox", "import \"util.lox\" as u;\nprint u.value;"),
+            ("vendor/util.lox", "var value = 42;"),
+        ],
+        &["--module-path={dir}/vendor"],
+    );
+    assert_eq!(output.stdout, "42\n", "{}", output.stderr);
+
+    let output = run_files(&[("main.lox", "import \"util.lox\" as u;")], &[]);
+    assert_eq!(output.code, Some(70));
+    assert!(output.stderr.contains("Cannot find module 'util.lox'."));
+}
@@ -0,0 +1,230 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn number_literals_take_bases_exponents_and_separators() {
+    let source = r#"
+        print 0xff;
+        print 0b1010;
+        print 0o17;
+        print 1_000_000;
+        print 1.5e3;
+        print 2E-2;
+        print 0xFF_FF;
+    "#;
+    assert_eq!(output(source), "255\n10\n15\n1000000\n1500\n0.02\n65535\n");
+}
+
+#[test]
+fn malformed_number_literals_are_scanner_errors() {
+    for literal in ["1__0", "0x", "1_", "0b12", "1e"] {
+        let stderr = error(&format!("print {};", literal), 65);
+        assert!(
+            stderr.starts_with(&format!(
+                "[line 1] Error: Invalid number literal '{}'.",
+                literal
+            )),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn modulo_exponent_and_integer_division() {
+    let source = r#"
+        print 7 % 3;
+        print -7 % 3;
+        print 7 % -3;
+        print 7.5 % 2;
+        print 7 ~/ 2;
+        print -7 ~/ 2;
+        print 2 ** 10;
+        print 2 ** 3 ** 2;
+        print -2 ** 2;
+        print 1 + 2 * 3 % 4;
+    "#;
+    assert_eq!(output(source), "1\n2\n-2\n1.5\n3\n-4\n1024\n512\n-4\n3\n");
+}
+
+#[test]
+fn integer_division_by_zero_is_an_error() {
+    assert_eq!(
+        error("print 1 ~/ 0;", 70),
+        "[line 1] Error at ~/: Division by zero.\n"
+    );
+    assert_eq!(output("print 1 / 0;"), "inf\n");
+}
+
+#[test]
+fn compound_assignment_and_increments_update_their_target() {
+    let source = r#"
+        var x = 1;
+        x += 2; print x;
+        x -= 1; print x;
+        x *= 5; print x;
+        x /= 4; print x;
+        x %= 2; print x;
+        var s = "a";
+        s += "b";
+        print s;
+        var i = 0;
+        print i++;
+        print i;
+        print ++i;
+        print i--;
+        print --i;
+    "#;
+    assert_eq!(output(source), "3\n2\n10\n2.5\n0.5\nab\n0\n1\n2\n2\n0\n");
+}
+
+#[test]
+fn updated_elements_evaluate_their_target_once() {
+    let source = r#"
+        var l = [1, 2];
+        l[0] += 10;
+        l[1]++;
+        print l;
+        var m = {"k": 1};
+        m["k"] *= 3;
+        print m;
+        var calls = 0;
+        fun key() { calls += 1; return 0; }
+        var k = [5];
+        k[key()] += 1;
+        print k;
+        print calls;
+    "#;
+    assert_eq!(output(source), "[11, 3]\n{\"k\": 3}\n[6]\n1\n");
+    assert!(error("var a = 1;\n(a)++;", 65).contains("Invalid assignment target."));
+    assert!(error("1 += 2;", 65).contains("Error at +=: Invalid assignment target."));
+}
+
+#[test]
+fn bitwise_operators_work_on_integral_numbers() {
+    let source = r#"
+        print 6 & 3;
+        print 6 | 3;
+        print 6 ^ 3;
+        print ~5;
+        print 1 << 4;
+        print -16 >> 2;
+        print 1 + 2 << 1;
+        print 5 & 4 == 4;
+    "#;
+    assert_eq!(output(source), "2\n7\n5\n-6\n16\n-4\n6\ntrue\n");
+}
+
+#[test]
+fn bitwise_errors_are_reported() {
+    let cases = [
+        ("1.5 & 1", "Error at &: Operands must be integers."),
+        ("\"a\" | 1", "Error at |: Operands must be integers."),
+        (
+            "1 << 64",
+            "Error at <<: Shift amount must be between 0 and 63.",
+        ),
+        (
+            "1 << -1",
+            "Error at <<: Shift amount must be between 0 and 63.",
+        ),
+        (
+            "1 << 63",
+            "Error at <<: Shift result doesn't fit in 64 bits.",
+        ),
+    ];
+    for (expression, message) in cases {
+        let stderr = error(&format!("print {};", expression), 70);
+        assert!(stderr.contains(message), "{}", stderr);
+    }
+}
+
+#[test]
+fn integers_stay_exact_until_they_meet_a_float() {
+    let source = r#"
+        print 2 ** 62;
+        print 7 / 2;
+        print 7 ~/ 2;
+        print int(3.9);
+        print float(2) / 4;
+        print int("42") + 1;
+        print {1: "a"}[1.0];
+    "#;
+    assert_eq!(
+        output(source),
+        "4611686018427387904\n3.5\n3\n3\n0.5\n43\na\n"
+    );
+}
+
+#[test]
+fn mixed_comparisons_are_exact_for_equality_and_ordering() {
+    let source = r#"
+        var big = 9007199254740993;
+        var float = 9007199254740992.0;
+        print big == float;
+        print big > float;
+        print float < big;
+        print 1 == 1.0;
+        print -2 < -1.5;
+        print -2 > -2.5;
+        print 9223372036854775807 < 9223372036854775808.0;
+    "#;
+    assert_eq!(
+        output(source),
+        "false\ntrue\ntrue\ntrue\ntrue\ntrue\ntrue\n"
+    );
+}
+
+#[test]
+fn remainders_and_integer_division_by_zero_are_errors() {
+    for expression in ["1 % 0", "1.5 % 0", "1.0 ~/ 0", "0 % 0.0"] {
+        let stderr = error(&format!("print {};", expression), 70);
+        assert!(stderr.contains("Division by zero."), "{}", stderr);
+    }
+}
+
+#[test]
+fn integers_promote_to_bigints_on_overflow() {
+    let source = r#"
+        print 9223372036854775807 + 1;
+        print 2 ** 100;
+        print (2 ** 100) ~/ (2 ** 98);
+        print -(2 ** 64) % 7;
+        print 2 ** 100 - 2 ** 100 + 5;
+        fun fact(n) { if (n <= 1) return 1; return n * fact(n - 1); }
+        print fact(30);
+        print int("123456789012345678901234567890");
+        print 10n == 10;
+        print "${5n + 1}";
+    "#;
+    assert_eq!(
+        output(source),
+        "9223372036854775808\n1267650600228229401496703205376\n4\n5\n5\n\
+         265252859812191058636308480000000\n123456789012345678901234567890\ntrue\n6\n"
+    );
+}
+
+#[test]
+fn bigints_compare_exactly_with_floats() {
+    let source = r#"
+        print 2 ** 64 == 18446744073709551616.0;
+        print 2 ** 64 > 1.5;
+        print 2 ** 64 > 1e300;
+        print -(2 ** 70) > -1e300;
+        print 2 ** 64 + 1 > 18446744073709551616.0;
+    "#;
+    assert_eq!(output(source), "true\ntrue\nfalse\ntrue\ntrue\n");
+}
+
+#[test]
+fn huge_bigints_hit_the_memory_limit() {
+    let source = "print 3 ** 10000000;";
+    for level in ["--opt-level=0", "--opt-level=1"] {
+        let output = common::run_with(source, &["--max-memory=100000", level]);
+        assert_eq!(output.code, Some(70));
+        assert_eq!(output.stderr, "Error: Out of memory (100000 byte limit).\n");
+    }
+    let optimized = common::run_with(source, &["--emit=optimized-ast"]);
+    assert_eq!(optimized.stdout, "(print (** 3 10000000))\n");
+}
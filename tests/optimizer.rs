@@ -0,0 +1,79 @@
+mod common;
+
+use common::run_with;
+
+// Runs `source` unoptimized and optimized, checks both behave the same and
+// returns what the optimized run printed.
+fn same_at_every_level(source: &str) -> String {
+    let plain = run_with(source, &["--opt-level=0"]);
+    let optimized = run_with(source, &["--opt-level=1"]);
+    assert_eq!(plain.stdout, optimized.stdout);
+    assert_eq!(plain.stderr, optimized.stderr);
+    assert_eq!(plain.code, optimized.code);
+    optimized.stdout
+}
+
+#[test]
+fn constant_expressions_and_branches_are_folded() {
+    let source = r#"
+        print 1 + 2 * 3;
+        print "a" + "b";
+        print !!true;
+        if (false) print "never"; else print "always";
+        while (false) print "never";
+        fun f() { return 1; print "dead"; }
+        print f();
+    "#;
+    let output = run_with(source, &["--emit=optimized-ast"]);
+    assert_eq!(
+        output.stdout,
+        "(print 7)\n(print \"ab\")\n(print true)\n(print \"always\")\n\
+         (fun f () (return 1))\n(print (call f))\n"
+    );
+    assert_eq!(same_at_every_level(source), "7\nab\ntrue\nalways\n1\n");
+}
+
+#[test]
+fn unreachable_let_bindings_still_shadow() {
+    let source = r#"
+        var x = "outer";
+        fun f() {
+          fun g() { return x; }
+          print g();
+          return;
+          let x = "inner";
+        }
+        f();
+    "#;
+    same_at_every_level(source);
+    let output = run_with(source, &[]);
+    assert_eq!(output.code, Some(70));
+    assert!(output
+        .stderr
+        .contains("Cannot access 'x' before initialization."));
+
+    let source = "var i = \"outer\";\nfor (let i = 0; false;) {}\nprint i;";
+    assert_eq!(same_at_every_level(source), "outer\n");
+}
+
+#[test]
+fn folding_leaves_runtime_errors_to_runtime() {
+    let output = run_with("print 1 - \"a\";", &["--emit=optimized-ast"]);
+    assert_eq!(output.stdout, "(print (- 1 \"a\"))\n");
+    same_at_every_level("print \"before\";\nprint 1 - \"a\";");
+    let output = run_with("print 1 - \"a\";", &[]);
+    assert_eq!(output.code, Some(70));
+    assert_eq!(
+        output.stderr,
+        "[line 1] Error at -: Operands must be numbers.\n"
+    );
+}
+
+#[test]
+fn invalid_options_are_usage_errors() {
+    let output = run_with("print 1;", &["--opt-level=2"]);
+    assert_eq!(output.code, Some(64));
+    assert!(output
+        .stderr
+        .starts_with("Invalid value '2' for --opt-level."));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn strings_compare_by_contents_whether_or_not_they_are_shared() {
+    let source = r#"
+        var greeting = "hello";
+        var copy = greeting;
+        print copy == greeting;
+        print "hel" + "lo" == greeting;
+        print greeting == "help";
+        fun shout(text) { return text + "!"; }
+        print shout(copy);
+    "#;
+    assert_eq!(output(source), "true\ntrue\nfalse\nhello!\n");
+}
+
+#[test]
+fn escape_sequences_are_decoded() {
+    let source = r#"
+        print "tab:\tend";
+        print "quote: \"hi\"";
+        print "back\\slash \u{1F600} \$";
+        print ["line\nbreak"];
+    "#;
+    assert_eq!(
+        output(source),
+        "tab:\tend\nquote: \"hi\"\nback\\slash \u{1F600} $\n[\"line\\nbreak\"]\n"
+    );
+    let stderr = error("print \"\\q\";", 65);
+    assert!(
+        stderr.contains("Invalid escape sequence '\\q'."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn interpolation_stringifies_values_and_ignores_shadowed_names() {
+    let source = r#"
+        var x = 2;
+        var str = "shadowed";
+        print "x=${x}, y=${x * 2}, list=${[x, "s"]}, ${nil}";
+        print "outer ${"inner ${x}"}";
+    "#;
+    assert_eq!(
+        output(source),
+        "x=2, y=4, list=[2, \"s\"], nil\nouter inner 2\n"
+    );
+}
+
+#[test]
+fn empty_interpolations_are_reported_at_the_hole() {
+    let stderr = error("print \"ok\";\nprint \"value: ${}\";", 65);
+    assert_eq!(stderr, "[line 2] Error at ${}: Empty interpolation.\n");
+}
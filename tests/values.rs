@@ -0,0 +1,38 @@
+mod common;
+
+use common::{error, output};
+
+#[test]
+fn conditional_and_null_coalescing_short_circuit() {
+    let source = r#"
+        print true ? "yes" : "no";
+        print 1 < 2 ? "a" : 2 < 3 ? "b" : "c";
+        print false ? 1 : nil ?? "fallback";
+        print 0 ?? 1;
+        var calls = 0;
+        fun f() { calls = calls + 1; return 1; }
+        print 1 ?? f();
+        print true ? 1 : f();
+        print calls;
+    "#;
+    assert_eq!(output(source), "yes\na\nfallback\n0\n1\n1\n0\n");
+}
+
+#[test]
+fn optional_chains_short_circuit_on_nil() {
+    let source = r#"
+        var none = nil;
+        print none?.name;
+        print none?.name.first;
+        print none?.name.first(1, 2);
+        print (none?.name) == nil;
+    "#;
+    assert_eq!(output(source), "nil\nnil\nnil\ntrue\n");
+
+    let stderr = error("var none = nil;\nprint none.name;", 70);
+    assert!(
+        stderr.contains("Only modules have properties."),
+        "{}",
+        stderr
+    );
+}